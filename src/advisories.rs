@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+
+/// Security advisory affecting a crate (e.g. from the RustSec advisory database).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Advisory {
+    /// Advisory identifier (e.g. `RUSTSEC-2021-0001`).
+    pub id: String,
+
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Semver requirements describing affected versions. All versions are affected if empty.
+    #[serde(default)]
+    pub affected: Vec<String>,
+}
+
+impl Advisory {
+    /// Returns true if the advisory affects the given package version.
+    ///
+    /// Fails if the version cannot be parsed, or if no affected version requirement matches
+    /// and some could not be parsed.
+    pub fn affects(&self, version: &str) -> Result<bool> {
+        if self.affected.is_empty() {
+            return Ok(true);
+        }
+        let version = semver::Version::parse(version)
+            .context(format!("Invalid package version: {}", version))?;
+        let mut invalid_requirements = vec![];
+        for requirement in &self.affected {
+            match semver::VersionReq::parse(requirement) {
+                Ok(parsed) if parsed.matches(&version) => return Ok(true),
+                Ok(_) => {}
+                Err(_) => invalid_requirements.push(requirement.as_str()),
+            }
+        }
+        if !invalid_requirements.is_empty() {
+            return Err(anyhow::format_err!(
+                "Invalid affected version requirements: {}",
+                invalid_requirements.join(", ")
+            ));
+        }
+        Ok(false)
+    }
+}
+
+/// Advisories keyed by crate name.
+pub type Advisories = std::collections::BTreeMap<String, Vec<Advisory>>;
+
+/// Read advisories from a JSON file mapping crate names to advisory lists.
+///
+/// Advisories are not fetched, the caller provides them (e.g. exported from RustSec).
+pub fn read(path: &std::path::Path) -> Result<Advisories> {
+    let file = std::fs::File::open(&path).context(format!(
+        "Failed to open advisories file: {}",
+        path.display()
+    ))?;
+    let reader = std::io::BufReader::new(file);
+    let advisories: Advisories = serde_json::from_reader(reader).context(format!(
+        "Failed to parse advisories file: {}",
+        path.display()
+    ))?;
+    Ok(advisories)
+}
+
+/// Advisories cross-checked against resolved packages.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Annotations {
+    /// Advisories affecting each package. Only affected packages are included.
+    pub affected: std::collections::BTreeMap<openfare_lib::package::Package, Vec<Advisory>>,
+
+    /// Advisories which could not be checked against a package, with the reason (e.g. an
+    /// invalid affected version requirement). These are not treated as affecting the package.
+    pub unchecked: Vec<(openfare_lib::package::Package, Advisory, String)>,
+}
+
+/// Returns packages which are affected by at least one advisory.
+pub fn annotate(
+    dependencies_locks: &crate::locks::DependenciesLocks,
+    advisories: &Advisories,
+) -> Annotations {
+    let mut annotations = Annotations::default();
+    for package in dependencies_locks.keys() {
+        let mut package_advisories = vec![];
+        for advisory in advisories.get(&package.name).into_iter().flatten() {
+            match advisory.affects(&package.version) {
+                Ok(true) => package_advisories.push(advisory.clone()),
+                Ok(false) => {}
+                Err(error) => annotations.unchecked.push((
+                    package.clone(),
+                    advisory.clone(),
+                    format!("{:#}", error),
+                )),
+            }
+        }
+        if !package_advisories.is_empty() {
+            annotations
+                .affected
+                .insert(package.clone(), package_advisories);
+        }
+    }
+    annotations
+}
@@ -0,0 +1,208 @@
+use anyhow::{format_err, Result};
+
+/// Archive extraction size limits.
+///
+/// Guards against decompression bombs in third party archives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractionLimits {
+    /// Maximum total extracted size in bytes.
+    pub max_total_size: u64,
+
+    /// Maximum extracted size of a single file in bytes.
+    pub max_file_size: u64,
+}
+
+impl Default for ExtractionLimits {
+    fn default() -> Self {
+        Self {
+            max_total_size: 1024 * 1024 * 1024,
+            max_file_size: 256 * 1024 * 1024,
+        }
+    }
+}
+
+impl ExtractionLimits {
+    /// Returns limits set using the OPENFARE_RS_MAX_EXTRACTED_SIZE and
+    /// OPENFARE_RS_MAX_EXTRACTED_FILE_SIZE environment variables (bytes), otherwise defaults.
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        Ok(Self {
+            max_total_size: env_bytes("OPENFARE_RS_MAX_EXTRACTED_SIZE")?
+                .unwrap_or(defaults.max_total_size),
+            max_file_size: env_bytes("OPENFARE_RS_MAX_EXTRACTED_FILE_SIZE")?
+                .unwrap_or(defaults.max_file_size),
+        })
+    }
+}
+
+/// Returns the byte count set using the given environment variable, if any.
+pub fn env_bytes(variable: &str) -> Result<Option<u64>> {
+    match std::env::var(variable) {
+        Ok(value) => Ok(Some(value.trim().parse::<u64>().map_err(|_| {
+            format_err!(
+                "Invalid {variable} value (expected bytes): {value}",
+                variable = variable,
+                value = value
+            )
+        })?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Gzip file header magic bytes.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Zstandard frame magic bytes.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Tar header magic (`ustar`, POSIX and GNU formats) and its offset in the first header.
+const TAR_MAGIC: &[u8] = b"ustar";
+const TAR_MAGIC_OFFSET: usize = 257;
+
+/// Number of leading bytes included in the error for unrecognized content.
+const SNIFF_LENGTH: usize = 64;
+
+/// Tar archive compression format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zstd,
+    /// Uncompressed tar, e.g. a locally built or mirror provided archive.
+    None,
+}
+
+/// Detect archive compression format from the file magic header.
+///
+/// Registries or CDNs occasionally serve an HTML error page with a success status.
+fn detect_compression(archive_path: &std::path::Path) -> Result<Compression> {
+    let mut file = std::fs::File::open(archive_path)?;
+    let head_length = TAR_MAGIC_OFFSET + TAR_MAGIC.len();
+    let mut head = Vec::with_capacity(head_length);
+    std::io::Read::read_to_end(
+        &mut std::io::Read::take(&mut file, head_length as u64),
+        &mut head,
+    )?;
+    if head.starts_with(&GZIP_MAGIC) {
+        return Ok(Compression::Gzip);
+    }
+    if head.starts_with(&ZSTD_MAGIC) {
+        return Ok(Compression::Zstd);
+    }
+    if head.get(TAR_MAGIC_OFFSET..) == Some(TAR_MAGIC) {
+        return Ok(Compression::None);
+    }
+    head.truncate(SNIFF_LENGTH);
+    Err(format_err!(
+        "Downloaded file is not a gzip or zstd archive: {path}. Leading bytes: {head:?}",
+        path = archive_path.display(),
+        head = String::from_utf8_lossy(&head)
+    ))
+}
+
+/// Open tar archive, decompressing according to the detected compression format.
+fn open_archive(archive_path: &std::path::Path) -> Result<tar::Archive<Box<dyn std::io::Read>>> {
+    let compression = detect_compression(archive_path)?;
+    let file = std::fs::File::open(archive_path)?;
+    let reader: Box<dyn std::io::Read> = match compression {
+        Compression::Gzip => Box::new(flate2::read::GzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(file)?),
+        Compression::None => Box::new(file),
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+/// Extracted archive.
+#[derive(Debug, Clone)]
+pub struct Extracted {
+    /// Archive top level directory if there is exactly one, otherwise the destination directory.
+    pub directory: std::path::PathBuf,
+
+    /// Total extracted size of archive files in bytes.
+    pub size: u64,
+}
+
+/// Extract gzip or zstd compressed, or uncompressed, tar archive into destination directory.
+///
+/// Aborts if extraction limits are exceeded.
+pub fn extract_tar_gz(
+    archive_path: &std::path::Path,
+    destination_directory: &std::path::Path,
+    limits: &ExtractionLimits,
+) -> Result<Extracted> {
+    let mut archive = open_archive(archive_path)?;
+    std::fs::create_dir_all(&destination_directory)?;
+
+    let mut total_size: u64 = 0;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let size = entry.header().size()?;
+        let path = entry.path()?.to_path_buf();
+        if size > limits.max_file_size {
+            return Err(format_err!(
+                "Archive file exceeds maximum extracted file size ({size} > {limit} bytes): {path}",
+                size = size,
+                limit = limits.max_file_size,
+                path = path.display()
+            ));
+        }
+        total_size += size;
+        if total_size > limits.max_total_size {
+            return Err(format_err!(
+                "Archive exceeds maximum total extracted size ({limit} bytes): {archive}",
+                limit = limits.max_total_size,
+                archive = archive_path.display()
+            ));
+        }
+        entry.unpack_in(&destination_directory)?;
+    }
+
+    let mut directories = std::fs::read_dir(&destination_directory)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path());
+    let directory = match (directories.next(), directories.next()) {
+        (Some(directory), None) if directory.is_dir() => directory,
+        _ => destination_directory.to_path_buf(),
+    };
+    Ok(Extracted {
+        directory,
+        size: total_size,
+    })
+}
+
+/// Read files located directly within the archive top level directory without extracting.
+///
+/// Returns file contents keyed by file name.
+pub fn read_top_level_files(
+    archive_path: &std::path::Path,
+    file_names: &[&str],
+    limits: &ExtractionLimits,
+) -> Result<std::collections::BTreeMap<String, Vec<u8>>> {
+    let mut archive = open_archive(archive_path)?;
+
+    let mut files = std::collections::BTreeMap::<_, _>::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_path_buf();
+        if path.components().count() != 2 {
+            continue;
+        }
+        let file_name = match path.file_name().and_then(|file_name| file_name.to_str()) {
+            Some(file_name) if file_names.contains(&file_name) => file_name.to_string(),
+            _ => continue,
+        };
+
+        let size = entry.header().size()?;
+        if size > limits.max_file_size {
+            return Err(format_err!(
+                "Archive file exceeds maximum extracted file size ({size} > {limit} bytes): {path}",
+                size = size,
+                limit = limits.max_file_size,
+                path = path.display()
+            ));
+        }
+        let mut bytes = Vec::new();
+        std::io::Read::read_to_end(&mut entry, &mut bytes)?;
+        files.insert(file_name, bytes);
+    }
+    Ok(files)
+}
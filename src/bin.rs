@@ -2,9 +2,27 @@ use openfare_lib::extension::FromLib;
 use openfare_rs_lib;
 
 fn main() {
-    let env = env_logger::Env::new().filter_or("OPENFARE_RS_LOG", "off");
-    env_logger::Builder::from_env(env).init();
+    let args: Vec<String> = std::env::args().collect();
+    if let Err(error) = openfare_rs_lib::logging::init() {
+        eprintln!("Failed to initialise logging: {}", error);
+    }
+
+    if let Err(error) = openfare_rs_lib::interrupt::install_handler() {
+        log::warn!("Failed to install interrupt handler: {}", error);
+    }
 
     let mut extension = openfare_rs_lib::RsExtension::new();
-    openfare_lib::extension::commands::run(&mut extension).unwrap();
+    if args.get(1).map(|arg| arg.as_str()) == Some("self-test") {
+        let report = extension.self_test(args.get(2).map(|arg| arg.as_str()));
+        print!("{}", report);
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+    if let Err(error) = openfare_lib::extension::commands::run(&mut extension) {
+        if openfare_rs_lib::interrupt::is_interrupted() {
+            eprintln!("Interrupted.");
+            std::process::exit(openfare_rs_lib::interrupt::EXIT_STATUS);
+        }
+        eprintln!("Error: {:?}", error);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,198 @@
+//! Portable bundle of resolved packages and their OpenFare locks.
+//!
+//! A bundle produced on a machine with network access can be read on an air-gapped machine.
+//! Lock files are stored as published. The same format is used for the resolved locks
+//! snapshot written into a project directory (`RESOLVED_FILE_NAME`).
+use anyhow::{format_err, Context, Result};
+
+/// Bundle format version written by this extension.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// File name of the resolved locks snapshot within a project directory.
+pub const RESOLVED_FILE_NAME: &str = "openfare.resolved.json";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Bundle {
+    #[serde(rename = "format-version")]
+    format_version: u32,
+    #[serde(rename = "primary-package")]
+    primary_package: Option<BundlePackage>,
+    dependencies: Vec<BundlePackage>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct BundlePackage {
+    registry: String,
+    name: String,
+    version: String,
+    /// OpenFare lock file contents as published.
+    lock: Option<String>,
+}
+
+impl BundlePackage {
+    /// Bundled package. The lock is serialized if its raw lock file is not available.
+    fn new(
+        package: &openfare_lib::package::Package,
+        lock: Option<&openfare_lib::lock::Lock>,
+        raw_locks: &crate::resolution::RawLocks,
+    ) -> Result<Self> {
+        let lock = match (raw_locks.get(package), lock) {
+            (Some(bytes), _) => Some(String::from_utf8(bytes.clone()).map_err(|_| {
+                format_err!(
+                    "OpenFare lock is not valid UTF-8: {} {}",
+                    package.name,
+                    package.version
+                )
+            })?),
+            (None, Some(lock)) => Some(serde_json::to_string_pretty(&lock)?),
+            (None, None) => None,
+        };
+        Ok(Self {
+            registry: package.registry.clone(),
+            name: package.name.clone(),
+            version: package.version.clone(),
+            lock,
+        })
+    }
+
+    fn package(&self) -> openfare_lib::package::Package {
+        openfare_lib::package::Package {
+            registry: self.registry.clone(),
+            name: self.name.clone(),
+            version: self.version.clone(),
+        }
+    }
+
+    fn raw_lock(&self) -> Result<Option<crate::locks::RawLock>> {
+        let lock = match &self.lock {
+            Some(lock) => lock,
+            None => return Ok(None),
+        };
+        let parsed = serde_json::from_str(lock).context(format!(
+            "Failed to parse bundled OpenFare lock: {} {}",
+            self.name, self.version
+        ))?;
+        Ok(Some(crate::locks::RawLock {
+            bytes: lock.as_bytes().to_vec(),
+            lock: parsed,
+            signature: None,
+        }))
+    }
+}
+
+/// Write resolved package locks and their raw lock files to a bundle file.
+pub fn write(
+    path: &std::path::Path,
+    resolution: &crate::resolution::Resolution<openfare_lib::package::PackageLocks>,
+) -> Result<()> {
+    write_sorted(path, resolution, None)
+}
+
+/// Write a bundle file with dependencies in the given order. Defaults to package order.
+pub fn write_sorted(
+    path: &std::path::Path,
+    resolution: &crate::resolution::Resolution<openfare_lib::package::PackageLocks>,
+    sort_order: Option<crate::locks::SortOrder>,
+) -> Result<()> {
+    let package_locks = &resolution.locks;
+    let dependencies: Vec<_> = match sort_order {
+        Some(sort_order) => {
+            crate::locks::sorted_packages(&package_locks.dependencies_locks, sort_order)
+        }
+        None => package_locks.dependencies_locks.keys().collect(),
+    };
+    let bundle = Bundle {
+        format_version: FORMAT_VERSION,
+        primary_package: match &package_locks.primary_package {
+            Some(package) => Some(BundlePackage::new(
+                package,
+                package_locks.primary_package_lock.as_ref(),
+                &resolution.raw_locks,
+            )?),
+            None => None,
+        },
+        dependencies: dependencies
+            .into_iter()
+            .map(|package| {
+                BundlePackage::new(
+                    package,
+                    package_locks.dependencies_locks[package].as_ref(),
+                    &resolution.raw_locks,
+                )
+            })
+            .collect::<Result<_>>()?,
+    };
+    let mut json = serde_json::to_string_pretty(&bundle)?;
+    json.push('\n');
+    std::fs::write(&path, json).context(format!("Failed to write bundle: {}", path.display()))?;
+    Ok(())
+}
+
+/// Read package locks and raw lock files from a bundle file.
+pub fn read(
+    path: &std::path::Path,
+) -> Result<crate::resolution::Resolution<openfare_lib::package::PackageLocks>> {
+    let contents = std::fs::read_to_string(&path)
+        .context(format!("Failed to read bundle: {}", path.display()))?;
+    let format_version = serde_json::from_str::<serde_json::Value>(&contents)?
+        .get("format-version")
+        .and_then(|version| version.as_u64());
+    if format_version != Some(FORMAT_VERSION as u64) {
+        return Err(format_err!(
+            "Unsupported bundle format version {:?} (expected {}): {}",
+            format_version,
+            FORMAT_VERSION,
+            path.display()
+        ));
+    }
+    let bundle: Bundle = serde_json::from_str(&contents)
+        .context(format!("Failed to parse bundle: {}", path.display()))?;
+
+    let mut resolution =
+        crate::resolution::Resolution::<openfare_lib::package::PackageLocks>::default();
+    if let Some(bundle_package) = &bundle.primary_package {
+        let package = bundle_package.package();
+        let raw_lock = bundle_package.raw_lock()?;
+        if let Some(raw_lock) = raw_lock {
+            resolution.locks.primary_package_lock = Some(raw_lock.lock);
+            resolution.raw_locks.insert(package.clone(), raw_lock.bytes);
+        }
+        resolution.locks.primary_package = Some(package);
+    }
+    for bundle_package in &bundle.dependencies {
+        let package = bundle_package.package();
+        let raw_lock = bundle_package.raw_lock()?;
+        let lock = match raw_lock {
+            Some(raw_lock) => {
+                resolution.raw_locks.insert(package.clone(), raw_lock.bytes);
+                Some(raw_lock.lock)
+            }
+            None => None,
+        };
+        resolution.locks.dependencies_locks.insert(package, lock);
+    }
+    Ok(resolution)
+}
+
+/// Write the resolved locks snapshot into a project directory. Returns the file path.
+///
+/// Packages are written in package order, so unchanged resolutions produce identical files.
+pub fn write_resolved(
+    project_path: &std::path::Path,
+    resolution: &crate::resolution::Resolution<openfare_lib::package::PackageLocks>,
+) -> Result<std::path::PathBuf> {
+    let path = project_path.join(RESOLVED_FILE_NAME);
+    write(&path, resolution)?;
+    Ok(path)
+}
+
+/// Read the resolved locks snapshot of a project directory, if present.
+pub fn read_resolved(
+    project_path: &std::path::Path,
+) -> Result<Option<crate::resolution::Resolution<openfare_lib::package::PackageLocks>>> {
+    let path = project_path.join(RESOLVED_FILE_NAME);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(read(&path)?))
+}
@@ -0,0 +1,307 @@
+//! Persistent crate archive and registry response cache.
+//!
+//! Published crate archives are immutable, so downloaded `.crate` files are kept under
+//! `<cache directory>/archives/<registry>/<name>-<version>.crate` and reused across runs.
+//! Mutable registry API responses are kept under `<cache directory>/responses` with their
+//! `ETag`/`Last-Modified` validators and revalidated on each use.
+//!
+//! Set OPENFARE_RS_NO_CACHE to disable the cache. OPENFARE_RS_CACHE_MAX_SIZE limits the total
+//! archive size in bytes: least recently accessed archives are evicted first.
+use anyhow::{Context, Result};
+
+/// Cached archive file name extension.
+const ARCHIVE_EXTENSION: &str = "crate";
+
+/// Distinguishes temporary files of concurrent insertions within this process.
+static INSERT_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Returns true unless the cache is disabled using OPENFARE_RS_NO_CACHE.
+pub fn is_enabled() -> bool {
+    std::env::var_os("OPENFARE_RS_NO_CACHE")
+        .filter(|value| !value.is_empty())
+        .is_none()
+}
+
+/// Returns the maximum cache size set using OPENFARE_RS_CACHE_MAX_SIZE (bytes), if any.
+pub fn max_size() -> Result<Option<u64>> {
+    crate::archive::env_bytes("OPENFARE_RS_CACHE_MAX_SIZE")
+}
+
+/// Returns the archive cache directory.
+pub fn archives_directory() -> Result<std::path::PathBuf> {
+    Ok(crate::common::cache_directory()?.join("archives"))
+}
+
+/// Returns the registry response cache directory.
+pub fn responses_directory() -> Result<std::path::PathBuf> {
+    Ok(crate::common::cache_directory()?.join("responses"))
+}
+
+/// Returns the cached archive path of a package.
+///
+/// Archives are grouped by registry URL so that mirrors never share entries.
+fn archive_path(
+    registry_url: &str,
+    package_name: &str,
+    package_version: &str,
+) -> Result<std::path::PathBuf> {
+    let registry_url = registry_url
+        .split_once("://")
+        .map(|(_scheme, rest)| rest)
+        .unwrap_or(registry_url);
+    let registry_directory: String = registry_url
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Ok(archives_directory()?.join(registry_directory).join(format!(
+        "{}-{}.{}",
+        package_name, package_version, ARCHIVE_EXTENSION
+    )))
+}
+
+/// Returns the cached archive of a package, if any, and marks it as accessed.
+pub fn get(
+    registry_url: &str,
+    package_name: &str,
+    package_version: &str,
+) -> Result<Option<std::path::PathBuf>> {
+    if !is_enabled() {
+        return Ok(None);
+    }
+    let path = archive_path(registry_url, package_name, package_version)?;
+    if !path.is_file() {
+        return Ok(None);
+    }
+    // Set explicitly: file systems mounted with noatime or relatime do not track reads.
+    if let Err(error) = filetime::set_file_atime(&path, filetime::FileTime::now()) {
+        log::debug!(
+            "Failed to update cached archive access time {}: {}",
+            path.display(),
+            error
+        );
+    }
+    log::debug!("Using cached archive: {}", path.display());
+    Ok(Some(path))
+}
+
+/// Store a downloaded package archive, then evict archives beyond the maximum cache size.
+///
+/// Failures are logged rather than returned since the cache is only an optimization.
+pub fn insert(
+    registry_url: &str,
+    package_name: &str,
+    package_version: &str,
+    archive: &std::path::Path,
+) {
+    if !is_enabled() {
+        return;
+    }
+    if let Err(error) = try_insert(registry_url, package_name, package_version, archive) {
+        log::warn!(
+            "Failed to cache archive of {} {}: {:#}",
+            package_name,
+            package_version,
+            error
+        );
+    }
+}
+
+fn try_insert(
+    registry_url: &str,
+    package_name: &str,
+    package_version: &str,
+    archive: &std::path::Path,
+) -> Result<()> {
+    let path = archive_path(registry_url, package_name, package_version)?;
+    let directory = path
+        .parent()
+        .ok_or_else(|| anyhow::format_err!("Invalid cache path: {}", path.display()))?;
+    std::fs::create_dir_all(&directory).context(format!(
+        "Failed to create cache directory: {}",
+        directory.display()
+    ))?;
+
+    // Copy then rename so that readers never see a partially written archive.
+    let tmp_path = directory.join(format!(
+        ".{}-{}.{}.{}.tmp",
+        package_name,
+        package_version,
+        std::process::id(),
+        INSERT_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    ));
+    std::fs::copy(&archive, &tmp_path)?;
+    if let Err(error) = std::fs::rename(&tmp_path, &path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(error.into());
+    }
+    log::debug!("Cached archive: {}", path.display());
+
+    if let Some(max_size) = max_size()? {
+        prune(&archives_directory()?, max_size)?;
+    }
+    Ok(())
+}
+
+/// Cached archive file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct CachedArchive {
+    accessed: filetime::FileTime,
+    path: std::path::PathBuf,
+    size: u64,
+}
+
+/// Returns files within the given directory and its subdirectories, with their metadata.
+fn files(directory: &std::path::Path) -> Result<Vec<(std::path::PathBuf, std::fs::Metadata)>> {
+    let mut files = Vec::new();
+    if !directory.is_dir() {
+        return Ok(files);
+    }
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in std::fs::read_dir(&directory)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                directories.push(entry.path());
+            } else if metadata.is_file() {
+                files.push((entry.path(), metadata));
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Returns cached archives within the given directory and its subdirectories.
+fn cached_archives(directory: &std::path::Path) -> Result<Vec<CachedArchive>> {
+    Ok(files(directory)?
+        .into_iter()
+        .filter(|(path, _metadata)| {
+            path.extension().and_then(|extension| extension.to_str()) == Some(ARCHIVE_EXTENSION)
+        })
+        .map(|(path, metadata)| CachedArchive {
+            accessed: filetime::FileTime::from_last_access_time(&metadata),
+            path,
+            size: metadata.len(),
+        })
+        .collect())
+}
+
+/// Evict least recently accessed archives until their total size is at most `max_size`.
+///
+/// Only `.crate` files are considered. Archives accessed at the same time are evicted in
+/// path order. Returns evicted archive paths in eviction order.
+pub fn prune(directory: &std::path::Path, max_size: u64) -> Result<Vec<std::path::PathBuf>> {
+    let mut archives = cached_archives(directory)?;
+    archives.sort();
+    let mut total_size: u64 = archives.iter().map(|archive| archive.size).sum();
+    let mut evicted = Vec::new();
+    for archive in archives {
+        if total_size <= max_size {
+            break;
+        }
+        match std::fs::remove_file(&archive.path) {
+            Ok(()) => {}
+            // Already evicted by a concurrent run.
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(error).context(format!(
+                    "Failed to evict cached archive: {}",
+                    archive.path.display()
+                ))
+            }
+        }
+        log::debug!("Evicted cached archive: {}", archive.path.display());
+        total_size -= archive.size;
+        evicted.push(archive.path);
+    }
+    Ok(evicted)
+}
+
+/// Cached registry response body with its HTTP validators.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CachedResponse {
+    /// `ETag` response header, sent back as `If-None-Match`.
+    pub etag: Option<String>,
+
+    /// `Last-Modified` response header, sent back as `If-Modified-Since`.
+    pub last_modified: Option<String>,
+
+    pub body: String,
+}
+
+/// Returns the cached response path for a URL.
+fn response_path(url: &str) -> Result<std::path::PathBuf> {
+    Ok(responses_directory()?.join(format!("{}.json", crate::locks::lock_hash(url.as_bytes()))))
+}
+
+/// Returns the cached response for a URL, if any.
+///
+/// Unreadable entries are ignored: the response is then fetched in full.
+pub fn get_response(url: &str) -> Option<CachedResponse> {
+    if !is_enabled() {
+        return None;
+    }
+    let path = response_path(url).ok()?;
+    let contents = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(response) => Some(response),
+        Err(error) => {
+            log::debug!(
+                "Ignoring invalid cached response {}: {}",
+                path.display(),
+                error
+            );
+            None
+        }
+    }
+}
+
+/// Store a response for a URL. Responses without validators are not stored.
+///
+/// Failures are logged rather than returned since the cache is only an optimization.
+pub fn insert_response(url: &str, response: &CachedResponse) {
+    if !is_enabled() || (response.etag.is_none() && response.last_modified.is_none()) {
+        return;
+    }
+    let result = response_path(url).and_then(|path| {
+        let directory = responses_directory()?;
+        std::fs::create_dir_all(&directory)?;
+        let tmp_path = directory.join(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            INSERT_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+        ));
+        std::fs::write(&tmp_path, serde_json::to_string(&response)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    });
+    if let Err(error) = result {
+        log::warn!("Failed to cache response of {}: {:#}", url, error);
+    }
+}
+
+/// Remove all cached archives and registry responses. Returns the number of bytes freed.
+pub fn clear() -> Result<u64> {
+    let mut size = 0;
+    for directory in [archives_directory()?, responses_directory()?] {
+        if !directory.exists() {
+            continue;
+        }
+        size += files(&directory)?
+            .iter()
+            .map(|(_path, metadata)| metadata.len())
+            .sum::<u64>();
+        std::fs::remove_dir_all(&directory).context(format!(
+            "Failed to clear cache directory: {}",
+            directory.display()
+        ))?;
+    }
+    log::info!("Cleared {} bytes of cache.", size);
+    Ok(size)
+}
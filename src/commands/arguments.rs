@@ -0,0 +1,371 @@
+use anyhow::Result;
+use structopt::StructOpt;
+
+/// Extension specific arguments given via `extension_args`.
+#[derive(Debug, Clone, Default, StructOpt)]
+#[structopt(name = "openfare-rs", no_version)]
+pub struct Arguments {
+    /// Only include dependencies which provide an OpenFare lock.
+    #[structopt(long = "with-locks-only")]
+    pub with_locks_only: bool,
+
+    /// Rustup toolchain used for rustc invocations during resolution.
+    ///
+    /// Defaults to the OPENFARE_RS_TOOLCHAIN environment variable if set. Ignored if `--rustc`
+    /// is given.
+    #[structopt(long = "toolchain")]
+    pub toolchain: Option<String>,
+
+    /// Path to the rustc binary used during resolution.
+    #[structopt(long = "rustc", parse(from_os_str))]
+    pub rustc: Option<std::path::PathBuf>,
+
+    /// Only resolve workspace default members and their dependencies.
+    #[structopt(long = "default-members-only")]
+    pub default_members_only: bool,
+
+    /// Only resolve this workspace member and its dependencies.
+    ///
+    /// A glob pattern (e.g. `tokio-*`) instead selects resolved packages whose name matches.
+    #[structopt(short = "p", long = "package")]
+    pub package: Option<String>,
+
+    /// Only report resolved dependencies whose name matches this glob pattern (e.g. `tokio-*`).
+    #[structopt(long = "filter")]
+    pub filter: Option<String>,
+
+    /// Space or comma separated list of features to activate.
+    #[structopt(long = "features", number_of_values = 1)]
+    pub features: Vec<String>,
+
+    /// Activate all available features.
+    #[structopt(long = "all-features")]
+    pub all_features: bool,
+
+    /// Do not activate the `default` feature.
+    #[structopt(long = "no-default-features")]
+    pub no_default_features: bool,
+
+    /// Feature configuration of a feature matrix entry: default, all, none or a list of
+    /// features. May be given several times (feature matrix only).
+    #[structopt(long = "feature-set", number_of_values = 1)]
+    pub feature_sets: Vec<crate::registries::crates::FeatureSet>,
+
+    /// Include all optional dependencies of workspace members, even if their gating feature is
+    /// not activated.
+    ///
+    /// Optional dependencies of other packages are only included if activated, e.g. using
+    /// `--features <dependency>/<feature>`.
+    #[structopt(long = "include-optional")]
+    pub include_optional: bool,
+
+    /// Cfg option (e.g. `unix` or `target_os="linux"`) selecting platform specific dependencies.
+    /// May be given several times.
+    ///
+    /// By default dependencies for every platform are included. Once given, only
+    /// `cfg(...)` target dependencies matching exactly the given cfgs are included.
+    #[structopt(long = "cfg", number_of_values = 1)]
+    pub cfg: Vec<String>,
+
+    /// Read OpenFare locks of git dependencies without one from submodules within them.
+    ///
+    /// Cargo initializes git dependency submodules recursively, so nothing more is fetched.
+    /// Git dependency package directories are walked for submodules, which may considerably
+    /// increase run time for large checkouts.
+    #[structopt(long = "git-submodules")]
+    pub git_submodules: bool,
+
+    /// Stream dependency locks to this file as JSON Lines records instead of returning them.
+    ///
+    /// Each line is `{"package": {...}, "lock": {...} | null}`. Options which need all
+    /// resolved locks at once cannot be combined with streaming.
+    #[structopt(
+        long = "jsonl",
+        parse(from_os_str),
+        conflicts_with_all = &[
+            "filter",
+            "with_locks_only",
+            "since",
+            "advisories",
+            "coverage_report",
+            "require_all_locks",
+            "verify_signatures",
+            "merge_registries",
+        ]
+    )]
+    pub jsonl: Option<std::path::PathBuf>,
+
+    /// Print the dependency tree to stderr, marking packages which provide an OpenFare lock.
+    ///
+    /// Machine output on stdout is unaffected.
+    #[structopt(long = "tree", conflicts_with = "jsonl")]
+    pub tree: bool,
+
+    /// Print dependencies to stderr in separate runtime, build and dev-dependency sections,
+    /// marking packages which provide an OpenFare lock.
+    ///
+    /// Machine output on stdout is unaffected.
+    #[structopt(long = "separate-kinds", conflicts_with = "jsonl")]
+    pub separate_kinds: bool,
+
+    /// Write the resolved locks into the project directory as `openfare.resolved.json`.
+    ///
+    /// The file can be read back using `bundle::read_resolved` without resolving again.
+    #[structopt(long = "write-resolved", conflicts_with = "jsonl")]
+    pub write_resolved: bool,
+
+    /// Include dev-dependencies of the audited crate (package command only).
+    ///
+    /// Excluded by default since consumers of a crate never build its dev-dependencies.
+    #[structopt(long = "include-dev-dependencies")]
+    pub include_dev_dependencies: bool,
+
+    /// Registry host name of the audited crate (package command only).
+    ///
+    /// Defaults to crates.io. Registries configured using OPENFARE_RS_REGISTRIES are selected
+    /// by the host name of their URL.
+    #[structopt(long = "registry")]
+    pub registry: Option<String>,
+
+    /// Require Cargo.lock to be up to date. Resolution fails if it would need updating.
+    #[structopt(long = "locked")]
+    pub locked: bool,
+
+    /// Require Cargo.lock to be up to date and forbid network access during resolution.
+    #[structopt(long = "frozen")]
+    pub frozen: bool,
+
+    /// Order of streamed (`--jsonl`) records: name, version or locked.
+    ///
+    /// Sorting buffers records until all locks are read.
+    #[structopt(long = "sort-by", requires = "jsonl")]
+    pub sort_by: Option<crate::locks::SortOrder>,
+
+    /// Remove all cached crate archives before resolving.
+    ///
+    /// The cache size can instead be bounded using OPENFARE_RS_CACHE_MAX_SIZE (bytes).
+    #[structopt(long = "clear-cache")]
+    pub clear_cache: bool,
+
+    /// Ignore registry versions published after this time (RFC 3339 or YYYY-MM-DD).
+    ///
+    /// Reproduces version selection as of a past date, for the latest package version and
+    /// manifest-only mode. Versions yanked since are ignored as well. A date includes versions
+    /// published during that day (until 23:59:59 UTC).
+    #[structopt(long = "index-snapshot")]
+    pub index_snapshot: Option<crate::registries::snapshot::IndexSnapshot>,
+
+    /// Reject crate archives whose expected checksum is unavailable.
+    ///
+    /// By default archives are verified when a checksum is known (Cargo.lock, local index)
+    /// and downloaded unverified otherwise.
+    #[structopt(long = "require-checksum")]
+    pub require_checksum: bool,
+
+    /// Resolve the lowest version satisfying each dependency requirement.
+    ///
+    /// Uses cargo's minimal-versions resolution mode (`-Z minimal-versions`). Cargo.lock is
+    /// ignored and left unmodified.
+    #[structopt(long = "minimal-versions", conflicts_with_all = &["locked", "frozen"])]
+    pub minimal_versions: bool,
+
+    /// Continue past packages whose OpenFare lock cannot be downloaded or parsed.
+    ///
+    /// Such packages are reported without a lock and warned about.
+    #[structopt(long = "keep-going")]
+    pub keep_going: bool,
+
+    /// Download and cache the crate archives listed in a standalone Cargo.lock without
+    /// returning locks.
+    ///
+    /// A subsequent Cargo.lock run is served from the archive cache. The number of archives
+    /// fetched is reported in the diagnostics. Rejected for Cargo.toml projects: cargo
+    /// resolution does not use the archive cache.
+    #[structopt(long = "prefetch", conflicts_with_all = &["jsonl", "write_resolved"])]
+    pub prefetch: bool,
+
+    /// Maximum number of packages processed concurrently.
+    #[structopt(short = "j", long = "jobs")]
+    pub jobs: Option<usize>,
+
+    /// Cargo configuration override applied during resolution (e.g. `net.retry=5`).
+    #[structopt(long = "config", number_of_values = 1, validator = validate_config)]
+    pub config: Vec<String>,
+
+    /// Treat packages with the same name and version from different registries as one.
+    ///
+    /// For source replacement setups where registry labels differ but the crates are
+    /// identical. The crates.io package is kept where present.
+    #[structopt(long = "merge-registries")]
+    pub merge_registries: bool,
+
+    /// Base Cargo.lock: only report dependencies added or changed in version since.
+    ///
+    /// Packages of the base Cargo.lock which are no longer resolved are reported in the
+    /// diagnostics as removed packages.
+    #[structopt(long = "since", parse(from_os_str))]
+    pub since: Option<std::path::PathBuf>,
+
+    /// JSON file mapping crate names to advisories. Affected packages are reported.
+    #[structopt(long = "advisories", parse(from_os_str))]
+    pub advisories: Option<std::path::PathBuf>,
+
+    /// Write a JSON lock coverage summary to this file.
+    #[structopt(long = "coverage-report", parse(from_os_str))]
+    pub coverage_report: Option<std::path::PathBuf>,
+
+    /// Return an error if any dependency has no OpenFare lock.
+    #[structopt(long = "require-all-locks")]
+    pub require_all_locks: bool,
+
+    /// Package name exempt from `--require-all-locks`. May be given several times.
+    #[structopt(long = "allow", number_of_values = 1)]
+    pub allow: Vec<String>,
+
+    /// Verify detached OpenFare lock signatures (e.g. `OpenFare.lock.sig`).
+    ///
+    /// Each lock is reported as verified, unverified or unsigned in package details.
+    #[structopt(long = "verify-signatures")]
+    pub verify_signatures: bool,
+
+    /// Hex encoded Ed25519 public key trusted for lock signatures. May be given several times.
+    #[structopt(long = "public-key", number_of_values = 1)]
+    pub public_keys: Vec<String>,
+
+    /// Only read the project package and its OpenFare lock, skipping dependency resolution.
+    #[structopt(long = "primary-only")]
+    pub primary_only: bool,
+
+    /// Read declared crates.io dependencies from the project manifest without invoking cargo.
+    ///
+    /// Each dependency is resolved to the newest version matching its requirement. Faster and
+    /// more robust than full resolution, but less accurate: Cargo.lock is ignored, transitive
+    /// dependencies are omitted and path, git and alternative registry dependencies are skipped.
+    #[structopt(long = "manifest-only", conflicts_with_all = &["primary_only", "package"])]
+    pub manifest_only: bool,
+
+    /// Validate the project manifest thoroughly, reporting all problems found.
+    #[structopt(long = "strict")]
+    pub strict: bool,
+
+    /// Project Cargo.toml file. Bypasses searching for Cargo.toml from the working directory.
+    ///
+    /// Relative paths are resolved against the working directory.
+    #[structopt(long = "manifest-path", parse(from_os_str))]
+    pub manifest_path: Option<std::path::PathBuf>,
+
+    /// Dependency definition file name to use when several are identified (e.g. `Cargo.toml`).
+    #[structopt(long = "dependency-file")]
+    pub dependency_file: Option<String>,
+
+    /// Return an error if no project manifest is found.
+    #[structopt(long = "require-manifest")]
+    pub require_manifest: bool,
+
+    /// Increase log verbosity (-v: info, -vv: debug, -vvv: trace). Overrides OPENFARE_RS_LOG.
+    #[structopt(short = "v", long = "verbose", parse(from_occurrences))]
+    pub verbose: u8,
+
+    /// Only log errors. Overrides OPENFARE_RS_LOG.
+    #[structopt(short = "q", long = "quiet")]
+    pub quiet: bool,
+}
+
+impl Arguments {
+    /// Parse extension arguments.
+    pub fn from_extension_args(extension_args: &[String]) -> Result<Self> {
+        let args = std::iter::once("openfare-rs".to_string()).chain(extension_args.iter().cloned());
+        Ok(Self::from_iter_safe(args)?)
+    }
+
+    /// Log level filter requested via verbosity arguments.
+    pub fn log_level(&self) -> Option<log::LevelFilter> {
+        if self.quiet {
+            return Some(log::LevelFilter::Error);
+        }
+        match self.verbose {
+            0 => None,
+            1 => Some(log::LevelFilter::Info),
+            2 => Some(log::LevelFilter::Debug),
+            _ => Some(log::LevelFilter::Trace),
+        }
+    }
+
+    /// Glob patterns which resolved package names must match, from `--package` and `--filter`.
+    pub fn package_patterns(&self) -> Vec<&str> {
+        let package_pattern = self
+            .package
+            .as_deref()
+            .filter(|package| crate::common::is_glob(package));
+        package_pattern
+            .into_iter()
+            .chain(self.filter.as_deref())
+            .collect()
+    }
+
+    /// Workspace member selected using `--package`, unless given as a glob pattern.
+    fn package_member(&self) -> Option<String> {
+        self.package
+            .clone()
+            .filter(|package| !crate::common::is_glob(package))
+    }
+
+    /// Cargo dependency resolution options.
+    ///
+    /// Reads the base Cargo.lock given using `--since`, if any.
+    pub fn resolution_options(
+        &self,
+    ) -> anyhow::Result<crate::registries::crates::ResolutionOptions> {
+        if self.jsonl.is_some() && !self.package_patterns().is_empty() {
+            return Err(anyhow::format_err!(
+                "The argument '--jsonl' cannot be used with package glob patterns."
+            ));
+        }
+        let unchanged_packages = match &self.since {
+            Some(base_cargo_lock_path) => {
+                crate::registries::crates::cargo_lock_packages(base_cargo_lock_path)?
+            }
+            None => std::collections::BTreeSet::new(),
+        };
+        Ok(crate::registries::crates::ResolutionOptions {
+            toolchain: self
+                .toolchain
+                .clone()
+                .or_else(|| std::env::var("OPENFARE_RS_TOOLCHAIN").ok()),
+            rustc: self.rustc.clone(),
+            default_members_only: self.default_members_only,
+            config: self.config.clone(),
+            dependency_file: self.dependency_file.clone(),
+            package: self.package_member(),
+            features: self.features.clone(),
+            all_features: self.all_features,
+            no_default_features: self.no_default_features,
+            include_optional: self.include_optional,
+            git_submodules: self.git_submodules,
+            jobs: self.jobs,
+            exclude_dev_dependencies: false,
+            stream_locks: self.jsonl.clone(),
+            primary_package: None,
+            sort_by: self.sort_by,
+            locked: self.locked,
+            frozen: self.frozen,
+            cfg: self.cfg.clone(),
+            index_snapshot: self.index_snapshot,
+            require_checksum: self.require_checksum,
+            minimal_versions: self.minimal_versions,
+            keep_going: self.keep_going,
+            unchanged_packages,
+        })
+    }
+}
+
+/// Check cargo configuration override has the form `KEY=VALUE`.
+fn validate_config(value: String) -> std::result::Result<(), String> {
+    match value.split_once('=') {
+        Some((key, _value)) if !key.trim().is_empty() => Ok(()),
+        _ => Err(format!(
+            "Invalid cargo configuration override (expected KEY=VALUE): {}",
+            value
+        )),
+    }
+}
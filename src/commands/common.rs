@@ -0,0 +1,416 @@
+use anyhow::{Context, Result};
+
+/// Number of slowest packages logged at the end of a command.
+const SLOWEST_PACKAGES_COUNT: usize = 10;
+
+/// Apply the log level requested via verbosity extension arguments, if any.
+pub fn configure_logging(args: &super::arguments::Arguments) {
+    if let Some(level) = args.log_level() {
+        crate::logging::set_level(level);
+    }
+}
+
+/// Reject `--index-snapshot` unless versions are selected by this extension
+/// (`--manifest-only`).
+///
+/// The snapshot would otherwise be silently ignored: versions resolved by cargo or listed in a
+/// Cargo.lock are unaffected by it.
+pub fn check_index_snapshot(
+    args: &super::arguments::Arguments,
+    selects_versions: bool,
+) -> Result<()> {
+    if args.index_snapshot.is_some() && !selects_versions {
+        return Err(anyhow::format_err!(
+            "--index-snapshot only applies to --manifest-only resolution of a Cargo.toml: \
+            versions resolved by cargo or listed in a Cargo.lock are unaffected."
+        ));
+    }
+    Ok(())
+}
+
+/// Apply extension arguments to resolved dependencies locks.
+///
+/// Packages affected by advisories are annotated in their package details.
+pub fn process_dependencies_locks(
+    args: &super::arguments::Arguments,
+    session: &crate::session::Session,
+    dependencies_locks: &mut crate::locks::DependenciesLocks,
+    packages_details: &mut crate::resolution::PackagesDetails,
+) -> Result<()> {
+    let base_packages = since_base_packages(args)?;
+    record_removed_packages(session, base_packages.as_ref(), dependencies_locks.keys());
+    filter_dependencies_locks(args, base_packages.as_ref(), dependencies_locks);
+    check_dependencies_locks(args, session, dependencies_locks, packages_details)?;
+    if args.with_locks_only {
+        dependencies_locks.retain(|_package, lock| lock.is_some());
+    }
+    Ok(())
+}
+
+/// Apply extension arguments to the dependencies locks of workspace members.
+///
+/// Filters (`--since`, `--filter`, `--merge-registries`, `--with-locks-only`) apply to each
+/// member's dependencies locks. Everything else applies once to the members and their
+/// combined dependencies: packages removed since `--since`, duplicate versions, registry
+/// changes, advisories, `--coverage-report` and `--require-all-locks`.
+pub fn process_members_dependencies_locks(
+    args: &super::arguments::Arguments,
+    session: &crate::session::Session,
+    members_locks: &mut std::collections::BTreeMap<
+        openfare_lib::package::Package,
+        openfare_lib::package::PackageLocks,
+    >,
+    packages_details: &mut crate::resolution::PackagesDetails,
+) -> Result<()> {
+    let base_packages = since_base_packages(args)?;
+    record_removed_packages(
+        session,
+        base_packages.as_ref(),
+        members_locks.iter().flat_map(|(member, package_locks)| {
+            std::iter::once(member).chain(package_locks.dependencies_locks.keys())
+        }),
+    );
+    for package_locks in members_locks.values_mut() {
+        filter_dependencies_locks(
+            args,
+            base_packages.as_ref(),
+            &mut package_locks.dependencies_locks,
+        );
+    }
+    let combined_locks: crate::locks::DependenciesLocks = members_locks
+        .values()
+        .flat_map(|package_locks| package_locks.dependencies_locks.clone())
+        .collect();
+    check_dependencies_locks(args, session, &combined_locks, packages_details)?;
+    if args.with_locks_only {
+        for package_locks in members_locks.values_mut() {
+            package_locks
+                .dependencies_locks
+                .retain(|_package, lock| lock.is_some());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the packages of the base Cargo.lock given using `--since`, if any.
+fn since_base_packages(
+    args: &super::arguments::Arguments,
+) -> Result<Option<std::collections::BTreeSet<openfare_lib::package::Package>>> {
+    args.since
+        .as_ref()
+        .map(|base_cargo_lock_path| {
+            crate::registries::crates::cargo_lock_packages(base_cargo_lock_path)
+        })
+        .transpose()
+}
+
+/// Record packages of the base Cargo.lock which are no longer resolved.
+fn record_removed_packages<'a>(
+    session: &crate::session::Session,
+    base_packages: Option<&std::collections::BTreeSet<openfare_lib::package::Package>>,
+    resolved_packages: impl IntoIterator<Item = &'a openfare_lib::package::Package>,
+) {
+    let base_packages = match base_packages {
+        Some(base_packages) => base_packages,
+        None => return,
+    };
+    let resolved_packages: std::collections::BTreeSet<_> = resolved_packages.into_iter().collect();
+    session.add_removed_packages(
+        base_packages
+            .iter()
+            .filter(|package| !resolved_packages.contains(package))
+            .cloned()
+            .collect(),
+    );
+}
+
+/// Keep packages added or changed since the base Cargo.lock and matching `--filter`, then
+/// merge registries if requested.
+fn filter_dependencies_locks(
+    args: &super::arguments::Arguments,
+    base_packages: Option<&std::collections::BTreeSet<openfare_lib::package::Package>>,
+    dependencies_locks: &mut crate::locks::DependenciesLocks,
+) {
+    if let (Some(base_cargo_lock_path), Some(base_packages)) = (&args.since, base_packages) {
+        let total = dependencies_locks.len();
+        dependencies_locks.retain(|package, _lock| !base_packages.contains(package));
+        log::info!(
+            "Packages added or changed since {}: {} of {}",
+            base_cargo_lock_path.display(),
+            dependencies_locks.len(),
+            total
+        );
+    }
+
+    for pattern in args.package_patterns() {
+        let total = dependencies_locks.len();
+        dependencies_locks
+            .retain(|package, _lock| crate::common::glob_match(pattern, &package.name));
+        if dependencies_locks.is_empty() {
+            log::warn!("No packages of {} match pattern: {}", total, pattern);
+        } else {
+            log::info!(
+                "Packages matching pattern {}: {} of {}",
+                pattern,
+                dependencies_locks.len(),
+                total
+            );
+        }
+    }
+
+    if args.merge_registries {
+        for package in
+            crate::locks::merge_registries(dependencies_locks, crate::registries::crates::HOST_NAME)
+        {
+            log::debug!(
+                "Merged package from registry {}: {} {}",
+                package.registry,
+                package.name,
+                package.version
+            );
+        }
+    }
+}
+
+/// Record duplicate versions and registry changes, check advisories, write the coverage report
+/// and enforce `--require-all-locks`.
+fn check_dependencies_locks(
+    args: &super::arguments::Arguments,
+    session: &crate::session::Session,
+    dependencies_locks: &crate::locks::DependenciesLocks,
+    packages_details: &mut crate::resolution::PackagesDetails,
+) -> Result<()> {
+    session.add_duplicate_versions(crate::locks::duplicate_versions(dependencies_locks));
+    session.add_registry_changes(crate::locks::registry_changes(dependencies_locks));
+
+    if let Some(advisories_path) = &args.advisories {
+        let advisories = crate::advisories::read(advisories_path)?;
+        let annotations = crate::advisories::annotate(dependencies_locks, &advisories);
+        for (package, advisories) in &annotations.affected {
+            let has_lock = dependencies_locks
+                .get(package)
+                .map(|lock| lock.is_some())
+                .unwrap_or(false);
+            for advisory in advisories {
+                session.warn(
+                    crate::resolution::WarningCategory::Advisory,
+                    Some(package),
+                    format!(
+                        "Advisory {id} affects {name} {version} (OpenFare lock: {has_lock}): {title}",
+                        id = advisory.id,
+                        name = package.name,
+                        version = package.version,
+                        has_lock = has_lock,
+                        title = advisory.title.as_deref().unwrap_or("untitled"),
+                    ),
+                );
+            }
+            packages_details
+                .entry(package.clone())
+                .or_default()
+                .advisories = Some(
+                advisories
+                    .iter()
+                    .map(|advisory| advisory.id.clone())
+                    .collect(),
+            );
+        }
+        for (package, advisory, reason) in &annotations.unchecked {
+            session.warn(
+                crate::resolution::WarningCategory::Advisory,
+                Some(package),
+                format!(
+                    "Advisory {id} not checked against {name} {version}: {reason}",
+                    id = advisory.id,
+                    name = package.name,
+                    version = package.version,
+                    reason = reason,
+                ),
+            );
+        }
+    }
+
+    if let Some(coverage_report_path) = &args.coverage_report {
+        let coverage = crate::locks::coverage(dependencies_locks);
+        let mut json = serde_json::to_string_pretty(&coverage)?;
+        json.push('\n');
+        std::fs::write(&coverage_report_path, json).with_context(|| {
+            format!(
+                "Failed to write coverage report: {}",
+                coverage_report_path.display()
+            )
+        })?;
+    }
+
+    if args.require_all_locks {
+        let offenders: Vec<String> = dependencies_locks
+            .iter()
+            .filter(|(package, lock)| lock.is_none() && !args.allow.contains(&package.name))
+            .map(|(package, _lock)| format!("{} {}", package.name, package.version))
+            .collect();
+        if !offenders.is_empty() {
+            return Err(anyhow::format_err!(
+                "Dependencies without OpenFare lock ({}): {}",
+                offenders.len(),
+                offenders.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Keep the lock statuses of the given packages: those remaining once extension arguments
+/// are applied (see `process_dependencies_locks`), and primary packages.
+///
+/// With `--merge-registries`, statuses of merged packages are kept for the package which
+/// replaces them, preferring a status with a lock.
+pub fn retain_lock_statuses<'a>(
+    args: &super::arguments::Arguments,
+    lock_statuses: &mut crate::resolution::LockStatuses,
+    packages: impl IntoIterator<Item = &'a openfare_lib::package::Package>,
+) {
+    let packages: std::collections::BTreeSet<_> = packages.into_iter().collect();
+    let mut retained = crate::resolution::LockStatuses::new();
+    for (package, status) in std::mem::take(lock_statuses) {
+        let kept = if packages.contains(&package) {
+            Some(package)
+        } else if args.merge_registries {
+            packages
+                .iter()
+                .find(|kept| kept.name == package.name && kept.version == package.version)
+                .map(|kept| (*kept).clone())
+        } else {
+            None
+        };
+        let kept = match kept {
+            Some(kept) => kept,
+            None => continue,
+        };
+        let replace = match retained.get(&kept) {
+            Some(existing) => existing.lock().is_none() && status.lock().is_some(),
+            None => true,
+        };
+        if replace {
+            retained.insert(kept, status);
+        }
+    }
+    *lock_statuses = retained;
+}
+
+/// Verify lock signatures if requested, recording the outcome in package details.
+pub fn verify_signatures(
+    args: &super::arguments::Arguments,
+    raw_locks: &crate::resolution::RawLocks,
+    signatures: &crate::resolution::RawLocks,
+    packages_details: &mut crate::resolution::PackagesDetails,
+) -> Result<()> {
+    if !args.verify_signatures {
+        return Ok(());
+    }
+    if args.public_keys.is_empty() {
+        return Err(anyhow::format_err!(
+            "--verify-signatures requires at least one --public-key."
+        ));
+    }
+    let public_keys = args
+        .public_keys
+        .iter()
+        .map(|public_key| crate::signatures::parse_public_key(public_key))
+        .collect::<Result<Vec<_>>>()?;
+    for (package, lock_bytes) in raw_locks {
+        let status = crate::signatures::verify(
+            lock_bytes,
+            signatures
+                .get(package)
+                .map(|signature| signature.as_slice()),
+            &public_keys,
+        );
+        if status == crate::signatures::SignatureStatus::Unverified {
+            log::warn!(
+                "OpenFare lock signature not verified: {} {}",
+                package.name,
+                package.version
+            );
+        }
+        packages_details
+            .entry(package.clone())
+            .or_default()
+            .signature = Some(status);
+    }
+    Ok(())
+}
+
+/// Print the dependency tree to stderr if requested using `--tree`.
+pub fn print_tree(
+    args: &super::arguments::Arguments,
+    package_locks: &openfare_lib::package::PackageLocks,
+    packages_details: &crate::resolution::PackagesDetails,
+) {
+    if args.tree {
+        eprint!("{}", crate::tree::render(package_locks, packages_details));
+    }
+}
+
+/// Print dependencies by kind to stderr if requested using `--separate-kinds`.
+pub fn print_kinds(
+    args: &super::arguments::Arguments,
+    dependencies_locks: &crate::locks::DependenciesLocks,
+    packages_details: &crate::resolution::PackagesDetails,
+) {
+    if !args.separate_kinds {
+        return;
+    }
+    let kinds_locks = crate::locks::separate_kinds(dependencies_locks, packages_details);
+    for (title, locks) in [
+        ("Runtime dependencies", &kinds_locks.runtime),
+        ("Build dependencies", &kinds_locks.build),
+        ("Dev dependencies", &kinds_locks.dev),
+    ] {
+        eprintln!("{} ({}):", title, locks.len());
+        for (package, lock) in locks {
+            let marker = if lock.is_some() {
+                format!(" {}", crate::tree::LOCK_MARKER)
+            } else {
+                String::new()
+            };
+            eprintln!("    {} v{}{}", package.name, package.version, marker);
+        }
+    }
+}
+
+/// Clear the archive cache before resolution if requested using `--clear-cache`.
+pub fn clear_cache(args: &super::arguments::Arguments) -> Result<()> {
+    if args.clear_cache {
+        crate::cache::clear()?;
+    }
+    Ok(())
+}
+
+/// Returns session diagnostics, logging a summary.
+pub fn diagnostics(session: &crate::session::Session) -> crate::resolution::Diagnostics {
+    let diagnostics = session.diagnostics();
+    log::info!(
+        "Downloaded {} bytes ({} bytes served from cache).",
+        diagnostics.downloaded_bytes,
+        diagnostics.cached_bytes
+    );
+    for (name, versions) in &diagnostics.duplicate_versions {
+        log::info!(
+            "Duplicate crate versions: {} ({})",
+            name,
+            versions.join(", ")
+        );
+    }
+    for package in &diagnostics.removed_packages {
+        log::info!("Removed package: {} {}", package.name, package.version);
+    }
+    for (package, duration) in diagnostics.slowest(SLOWEST_PACKAGES_COUNT) {
+        log::debug!(
+            "Slow package: {} {} ({} ms)",
+            package.name,
+            package.version,
+            duration.as_millis()
+        );
+    }
+    diagnostics
+}
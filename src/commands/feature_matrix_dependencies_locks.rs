@@ -0,0 +1,79 @@
+use anyhow::{format_err, Result};
+
+/// Resolve dependencies locks for each feature configuration given using `--feature-set`.
+///
+/// Each configuration is resolved independently, sharing the session and caches, so that
+/// feature gated dependencies can be compared. Results are keyed by configuration.
+pub fn feature_matrix_dependencies_locks(
+    extension: &crate::RsExtension,
+    working_directory: &std::path::Path,
+    extension_args: &[String],
+) -> Result<
+    crate::resolution::Resolution<
+        std::collections::BTreeMap<String, crate::locks::DependenciesLocks>,
+    >,
+> {
+    let args = super::arguments::Arguments::from_extension_args(extension_args)?;
+    super::common::configure_logging(&args);
+    super::common::clear_cache(&args)?;
+    if args.feature_sets.is_empty() {
+        return Err(format_err!(
+            "No feature configurations given. Use --feature-set (e.g. default, all, none or a \
+            list of features)."
+        ));
+    }
+    let session = crate::session::Session::new(extension.progress());
+    let working_directory = &crate::registries::crates::canonicalize_directory(working_directory)?;
+
+    let manifest_path = match &args.manifest_path {
+        Some(manifest_path) => working_directory.join(manifest_path),
+        None => working_directory
+            .join(crate::registries::crates::DependencyFileType::CargoToml.file_name()),
+    };
+    if !manifest_path.is_file() {
+        return Err(format_err!(
+            "Manifest not found: {}",
+            manifest_path.display()
+        ));
+    }
+
+    super::common::check_index_snapshot(&args, false)?;
+
+    let options = args.resolution_options()?;
+    let mut resolution = crate::resolution::Resolution::<
+        std::collections::BTreeMap<String, crate::locks::DependenciesLocks>,
+    >::default();
+    for feature_set in &args.feature_sets {
+        log::debug!("Resolving feature configuration: {}", feature_set);
+        let dependencies = crate::registries::crates::dependencies_locks(
+            &manifest_path,
+            &feature_set.resolution_options(&options),
+            &session,
+        )?;
+        let mut dependencies_locks = dependencies.locks;
+        let mut packages_details = dependencies.packages_details;
+        super::common::process_dependencies_locks(
+            &args,
+            &session,
+            &mut dependencies_locks,
+            &mut packages_details,
+        )?;
+        let mut lock_statuses = dependencies.lock_statuses;
+        super::common::retain_lock_statuses(&args, &mut lock_statuses, dependencies_locks.keys());
+        resolution
+            .locks
+            .insert(feature_set.to_string(), dependencies_locks);
+        resolution.packages_details.extend(packages_details);
+        resolution.raw_locks.extend(dependencies.raw_locks);
+        resolution.signatures.extend(dependencies.signatures);
+        resolution.lock_statuses.extend(lock_statuses);
+    }
+    super::common::verify_signatures(
+        &args,
+        &resolution.raw_locks,
+        &resolution.signatures,
+        &mut resolution.packages_details,
+    )?;
+    resolution.diagnostics = super::common::diagnostics(&session);
+    Ok(resolution)
+}
@@ -0,0 +1,84 @@
+use anyhow::{format_err, Result};
+use openfare_lib::extension::commands::project_dependencies_locks::ProjectDependenciesLocks;
+
+/// Git URL schemes accepted for cloning.
+static GIT_URL_SCHEMES: &[&str] = &["https", "http", "ssh", "git", "file"];
+
+/// Returns dependencies locks for a git repository at the given revision.
+///
+/// The repository is shallow cloned into a temporary directory, which is removed afterwards.
+/// Resolution then proceeds as for a local project.
+pub fn git_dependencies_locks(
+    extension: &crate::RsExtension,
+    git_url: &str,
+    rev: &str,
+    extension_args: &[String],
+) -> Result<crate::resolution::Resolution<ProjectDependenciesLocks>> {
+    check_git_url(git_url)?;
+    check_rev(rev)?;
+
+    let tmp_dir = crate::common::temp_dir()?;
+    let checkout_directory = tmp_dir.path().join("checkout");
+    std::fs::create_dir_all(&checkout_directory)?;
+    log::debug!(
+        "Cloning {} at {} into: {}",
+        git_url,
+        rev,
+        checkout_directory.display()
+    );
+    git(&checkout_directory, &["init", "--quiet"])?;
+    git(
+        &checkout_directory,
+        &["fetch", "--quiet", "--depth", "1", "--", git_url, rev],
+    )?;
+    git(
+        &checkout_directory,
+        &["checkout", "--quiet", "--detach", "FETCH_HEAD"],
+    )?;
+
+    super::project_dependencies_locks(extension, &checkout_directory, extension_args)
+}
+
+fn check_git_url(git_url: &str) -> Result<()> {
+    let url = url::Url::parse(git_url)
+        .map_err(|error| format_err!("Invalid git URL ({}): {}", error, git_url))?;
+    if !GIT_URL_SCHEMES.contains(&url.scheme()) {
+        return Err(format_err!(
+            "Unsupported git URL scheme '{}' (expected one of: {}): {}",
+            url.scheme(),
+            GIT_URL_SCHEMES.join(", "),
+            git_url
+        ));
+    }
+    Ok(())
+}
+
+/// Check revision is a plausible commit, branch or tag name.
+fn check_rev(rev: &str) -> Result<()> {
+    if rev.is_empty()
+        || rev.starts_with('-')
+        || rev
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || c == ':')
+    {
+        return Err(format_err!("Invalid git revision: {:?}", rev));
+    }
+    Ok(())
+}
+
+fn git(directory: &std::path::Path, args: &[&str]) -> Result<()> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(&directory)
+        .output()
+        .map_err(|error| format_err!("Failed to run git: {}", error))?;
+    if !output.status.success() {
+        return Err(format_err!(
+            "git {} failed ({}): {}",
+            args.first().unwrap_or(&""),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
@@ -1,5 +1,15 @@
+mod arguments;
+mod common;
+mod feature_matrix_dependencies_locks;
+mod git_dependencies_locks;
 mod package_dependencies_locks;
 mod project_dependencies_locks;
+mod validate_project_lock;
+mod workspace_dependencies_locks;
 
-pub use package_dependencies_locks::package_dependencies_locks;
-pub use project_dependencies_locks::project_dependencies_locks;
+pub use feature_matrix_dependencies_locks::feature_matrix_dependencies_locks;
+pub use git_dependencies_locks::git_dependencies_locks;
+pub use package_dependencies_locks::{locks_from_directory, package_dependencies_locks};
+pub use project_dependencies_locks::{primary_package_locks, project_dependencies_locks};
+pub use validate_project_lock::validate_project_lock;
+pub use workspace_dependencies_locks::workspace_dependencies_locks;
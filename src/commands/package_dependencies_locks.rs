@@ -5,74 +5,224 @@ pub fn package_dependencies_locks(
     extension: &crate::RsExtension,
     package_name: &str,
     package_version: &Option<&str>,
-    _extension_args: &Vec<String>,
-) -> Result<openfare_lib::extension::commands::package_dependencies_locks::PackageDependenciesLocks>
-{
+    extension_args: &[String],
+) -> Result<
+    crate::resolution::Resolution<
+        openfare_lib::extension::commands::package_dependencies_locks::PackageDependenciesLocks,
+    >,
+> {
+    let args = super::arguments::Arguments::from_extension_args(extension_args)?;
+    super::common::configure_logging(&args);
+    super::common::clear_cache(&args)?;
+    let session = crate::session::Session::new(extension.progress());
+
+    let registry_host_name = match &args.registry {
+        Some(registry_host_name) => registry_host_name.clone(),
+        None => extension.registries().first().cloned().ok_or_else(|| {
+            anyhow::format_err!("Code error: at least one registry host name expected.")
+        })?,
+    };
+    let registry = crate::registries::backend(&registry_host_name)?
+        .ok_or_else(|| anyhow::format_err!("Unsupported registry: {}", registry_host_name))?;
+
     let package_version = match package_version {
         Some(v) => v.to_string(),
         None => {
             log::debug!("No version argument given. Querying for latest version.");
-            crate::registries::crates::get_latest_version(&package_name)?.ok_or(
-                anyhow::format_err!("Failed to find latest version. Please specify version."),
-            )?
+            registry
+                .latest_version(package_name, args.index_snapshot.as_ref(), &session)?
+                .ok_or_else(|| {
+                    anyhow::format_err!("Failed to find latest version. Please specify version.")
+                })?
         }
     };
-    log::debug!("Found version: {}", package_version.to_string());
+    log::debug!("Found version: {}", package_version);
 
-    let tmp_dir = tempdir::TempDir::new("openfare_rs")?;
+    let tmp_dir = crate::common::temp_dir()?;
     let tmp_dir = tmp_dir.path().to_path_buf();
     log::debug!("Using temporary directory: {}", tmp_dir.display());
-    let package_directory = crate::registries::crates::setup_package_directory(
+    let options = crate::registries::crates::ResolutionOptions {
+        exclude_dev_dependencies: !args.include_dev_dependencies,
+        ..args.resolution_options()?
+    };
+    let package_directory = registry.fetch_package(
         &package_name,
         &package_version,
+        None,
+        options.require_checksum,
         &tmp_dir,
+        (1, 1),
+        &session,
     )?;
+    log::debug!(
+        "Fetched package archive: {}",
+        package_directory.download_url
+    );
+    let mut resolution = locks_from_directory(&package_directory.path, &options, &session)?;
+    set_primary_package_registry(&mut resolution, registry.host_name());
+    let mut package_locks = resolution.locks;
+    let mut packages_details = resolution.packages_details;
+    if let Some(primary_package) = &package_locks.primary_package {
+        if let Some(details) = packages_details.get_mut(primary_package) {
+            package_directory.update_details(details);
+        }
+    }
+    super::common::process_dependencies_locks(
+        &args,
+        &session,
+        &mut package_locks.dependencies_locks,
+        &mut packages_details,
+    )?;
+    let mut lock_statuses = resolution.lock_statuses;
+    super::common::retain_lock_statuses(
+        &args,
+        &mut lock_statuses,
+        package_locks.dependencies_locks.keys(),
+    );
+    super::common::verify_signatures(
+        &args,
+        &resolution.raw_locks,
+        &resolution.signatures,
+        &mut packages_details,
+    )?;
+    super::common::print_tree(&args, &package_locks, &packages_details);
+    super::common::print_kinds(&args, &package_locks.dependencies_locks, &packages_details);
+
+    Ok(crate::resolution::Resolution {
+        locks:
+            openfare_lib::extension::commands::package_dependencies_locks::PackageDependenciesLocks {
+                registry_host_name: registry.host_name().to_string(),
+                package_locks,
+            },
+        packages_details,
+        raw_locks: resolution.raw_locks,
+        signatures: resolution.signatures,
+        lock_statuses,
+        diagnostics: super::common::diagnostics(&session),
+    })
+}
 
-    let package = crate::registries::crates::get_package(&package_name, &package_version);
-    let lock = crate::registries::crates::get_lock(&package_directory)?;
+/// Label the primary package with the registry it was fetched from.
+///
+/// Packages read from a manifest are labelled as crates.io packages.
+fn set_primary_package_registry(
+    resolution: &mut crate::resolution::Resolution<openfare_lib::package::PackageLocks>,
+    registry_host_name: &str,
+) {
+    let package = match &resolution.locks.primary_package {
+        Some(package) if package.registry != registry_host_name => package.clone(),
+        _ => return,
+    };
+    let relabelled = openfare_lib::package::Package {
+        registry: registry_host_name.to_string(),
+        ..package.clone()
+    };
+    if let Some(details) = resolution.packages_details.remove(&package) {
+        resolution
+            .packages_details
+            .insert(relabelled.clone(), details);
+    }
+    if let Some(raw_lock) = resolution.raw_locks.remove(&package) {
+        resolution.raw_locks.insert(relabelled.clone(), raw_lock);
+    }
+    if let Some(signature) = resolution.signatures.remove(&package) {
+        resolution.signatures.insert(relabelled.clone(), signature);
+    }
+    resolution.locks.primary_package = Some(relabelled);
+}
 
-    let mut dependencies_locks = dependencies_locks(&package_directory)?;
-    dependencies_locks.remove(&package);
+/// Returns package locks for an extracted crate (or project) directory.
+///
+/// The primary package is excluded from the dependencies locks.
+pub fn locks_from_directory(
+    package_directory: &std::path::Path,
+    options: &crate::registries::crates::ResolutionOptions,
+    session: &crate::session::Session,
+) -> Result<crate::resolution::Resolution<openfare_lib::package::PackageLocks>> {
+    let cargo_toml_path = package_directory
+        .join(crate::registries::crates::DependencyFileType::CargoToml.file_name());
+    let package = crate::registries::crates::package_from_toml(&cargo_toml_path)?;
+    let raw_lock = crate::registries::crates::get_raw_lock(package_directory)?;
 
-    Ok(
-        openfare_lib::extension::commands::package_dependencies_locks::PackageDependenciesLocks {
-            registry_host_name: extension
-                .registries()
-                .first()
-                .ok_or(anyhow::format_err!(
-                    "Code error: at least one registry host name expected."
-                ))?
-                .to_string(),
-            package_locks: openfare_lib::package::PackageLocks {
-                primary_package: Some(package),
-                primary_package_lock: lock,
-                dependencies_locks,
-            },
+    // The primary package is reported separately, also when dependencies locks are streamed.
+    let options = crate::registries::crates::ResolutionOptions {
+        primary_package: package.clone(),
+        ..options.clone()
+    };
+    let dependencies = dependencies_locks(package_directory, &options, session)?;
+    let mut packages_details = dependencies.packages_details;
+    let mut raw_locks = dependencies.raw_locks;
+    let mut signatures = dependencies.signatures;
+    let mut lock_statuses = dependencies.lock_statuses;
+    let mut dependencies_locks = dependencies.locks;
+    if let Some(package) = &package {
+        packages_details.insert(
+            package.clone(),
+            crate::registries::crates::package_details_from_toml(&cargo_toml_path)?,
+        );
+        dependencies_locks.remove(package);
+        lock_statuses.remove(package);
+        match &raw_lock {
+            Some(raw_lock) => raw_locks.insert(package.clone(), raw_lock.bytes.clone()),
+            None => raw_locks.remove(package),
+        };
+        match raw_lock
+            .as_ref()
+            .and_then(|raw_lock| raw_lock.signature.clone())
+        {
+            Some(signature) => signatures.insert(package.clone(), signature),
+            None => signatures.remove(package),
+        };
+    }
+
+    Ok(crate::resolution::Resolution {
+        locks: openfare_lib::package::PackageLocks {
+            primary_package: package,
+            primary_package_lock: raw_lock.map(|raw_lock| raw_lock.lock),
+            dependencies_locks,
         },
-    )
+        packages_details,
+        raw_locks,
+        signatures,
+        lock_statuses,
+        ..Default::default()
+    })
 }
 
 fn dependencies_locks(
-    package_directory: &std::path::PathBuf,
-) -> Result<
-    std::collections::BTreeMap<openfare_lib::package::Package, Option<openfare_lib::lock::Lock>>,
-> {
+    package_directory: &std::path::Path,
+    options: &crate::registries::crates::ResolutionOptions,
+    session: &crate::session::Session,
+) -> Result<crate::resolution::Resolution<crate::locks::DependenciesLocks>> {
     // Identify all dependency definition files.
     let dependency_files =
-        match crate::registries::crates::identify_dependency_files(&package_directory) {
+        match crate::registries::crates::identify_dependency_files(package_directory)? {
             Some(v) => v,
             None => {
                 log::debug!("Did not identify any dependency definition files.");
-                return Ok(std::collections::BTreeMap::<_, _>::new());
+                return Ok(crate::resolution::Resolution::default());
             }
         };
-    let dependency_file = match dependency_files.first() {
+    let dependency_file = match crate::registries::crates::select_dependency_file(
+        dependency_files,
+        &options.dependency_file,
+    )? {
         Some(f) => f,
         None => {
             log::debug!("Did not identify any dependency definition files.");
-            return Ok(std::collections::BTreeMap::<_, _>::new());
+            return Ok(crate::resolution::Resolution::default());
         }
     };
-    let dependencies_locks = crate::registries::crates::dependencies_locks(&dependency_file.path)?;
-    Ok(dependencies_locks)
+    match dependency_file.r#type {
+        crate::registries::crates::DependencyFileType::CargoToml => {
+            crate::registries::crates::dependencies_locks(&dependency_file.path, options, session)
+        }
+        crate::registries::crates::DependencyFileType::CargoLock => {
+            crate::registries::crates::dependencies_locks_from_cargo_lock(
+                &dependency_file.path,
+                options,
+                session,
+            )
+        }
+    }
 }
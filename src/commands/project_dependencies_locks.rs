@@ -2,51 +2,374 @@ use anyhow::{format_err, Result};
 use openfare_lib::extension::commands::project_dependencies_locks::ProjectDependenciesLocks;
 
 pub fn project_dependencies_locks(
-    working_directory: &std::path::PathBuf,
-    _extension_args: &Vec<String>,
-) -> Result<ProjectDependenciesLocks> {
-    // Identify all dependency definition files.
-    let dependency_files =
-        match crate::registries::crates::identify_dependency_files(&working_directory) {
-            Some(v) => v,
-            None => {
-                log::debug!("Did not identify any dependency definition files.");
-                return Ok(ProjectDependenciesLocks::default());
-            }
-        };
-    let dependency_file = match dependency_files.first() {
-        Some(f) => f,
+    extension: &crate::RsExtension,
+    working_directory: &std::path::Path,
+    extension_args: &[String],
+) -> Result<crate::resolution::Resolution<ProjectDependenciesLocks>> {
+    let args = super::arguments::Arguments::from_extension_args(extension_args)?;
+    super::common::configure_logging(&args);
+    super::common::clear_cache(&args)?;
+    let session = crate::session::Session::new(extension.progress());
+    let resolution = resolve(working_directory, &args, &session)?;
+    if args.prefetch {
+        return Ok(resolution);
+    }
+    super::common::print_tree(
+        &args,
+        &resolution.locks.package_locks,
+        &resolution.packages_details,
+    );
+    super::common::print_kinds(
+        &args,
+        &resolution.locks.package_locks.dependencies_locks,
+        &resolution.packages_details,
+    );
+    if args.write_resolved {
+        write_resolved(&resolution)?;
+    }
+    Ok(resolution)
+}
+
+fn resolve(
+    working_directory: &std::path::Path,
+    args: &super::arguments::Arguments,
+    session: &crate::session::Session,
+) -> Result<crate::resolution::Resolution<ProjectDependenciesLocks>> {
+    let working_directory = &crate::registries::crates::canonicalize_directory(working_directory)?;
+
+    let manifest_path = match find_manifest(working_directory, args, session)? {
+        Some(manifest_path) => manifest_path,
         None => {
-            log::debug!("Did not identify any dependency definition files.");
-            return Ok(ProjectDependenciesLocks::default());
+            return Ok(crate::resolution::Resolution {
+                diagnostics: super::common::diagnostics(session),
+                ..Default::default()
+            })
         }
     };
 
     log::debug!(
         "Found dependency definitions file: {}",
-        dependency_file.path.display()
+        manifest_path.display()
     );
 
-    let project_path = dependency_file
-        .path
+    let project_path = manifest_path
         .parent()
-        .ok_or(format_err!(
-            "Failed to derive parent directory from dependency file path: {}",
-            dependency_file.path.display()
-        ))?
+        .ok_or_else(|| {
+            format_err!(
+                "Failed to derive parent directory from dependency file path: {}",
+                manifest_path.display()
+            )
+        })?
         .to_path_buf();
 
-    let primary_package = crate::registries::crates::package_from_toml(&dependency_file.path)?;
-    let primary_package_lock = crate::registries::crates::get_lock(&project_path)?;
+    let is_cargo_lock = crate::registries::crates::DependencyFileType::from_path(&manifest_path)
+        == Some(crate::registries::crates::DependencyFileType::CargoLock);
+    super::common::check_index_snapshot(args, args.manifest_only && !is_cargo_lock)?;
+    if is_cargo_lock {
+        if args.prefetch {
+            return prefetch(args, &manifest_path, &project_path, session);
+        }
+        return lockfile_dependencies_locks(args, &manifest_path, &project_path, session);
+    }
+    if args.prefetch {
+        // Cargo resolution downloads crates into cargo's own cache, not the archive cache.
+        return Err(format_err!(
+            "--prefetch requires a standalone Cargo.lock, found: {}",
+            manifest_path.display()
+        ));
+    }
 
-    let dependencies_locks = crate::registries::crates::dependencies_locks(&dependency_file.path)?;
+    if args.strict {
+        crate::registries::crates::check_manifest(&manifest_path)?;
+    }
+    if args.primary_only {
+        return Ok(crate::resolution::Resolution {
+            locks: ProjectDependenciesLocks {
+                project_path,
+                package_locks: primary_package_locks(&manifest_path, session)?,
+            },
+            diagnostics: super::common::diagnostics(session),
+            ..Default::default()
+        });
+    }
+    let mut primary_package = crate::registries::crates::package_from_toml(&manifest_path)?;
+    let mut primary_package_lock = primary_package_lock(&manifest_path, session)?;
 
-    Ok(ProjectDependenciesLocks {
-        project_path: project_path.to_path_buf(),
-        package_locks: openfare_lib::package::PackageLocks {
-            primary_package,
-            primary_package_lock,
-            dependencies_locks,
+    let mut options = args.resolution_options()?;
+    if options.package.is_none() {
+        options.primary_package = primary_package.clone();
+    }
+    let dependencies = if args.manifest_only {
+        crate::registries::crates::dependencies_locks_from_manifest(
+            &manifest_path,
+            &options,
+            session,
+        )?
+    } else {
+        crate::registries::crates::dependencies_locks(&manifest_path, &options, session)?
+    };
+    let mut packages_details = dependencies.packages_details;
+    let mut dependencies_locks = dependencies.locks;
+    if let Some(package_name) = &options.package {
+        // The selected workspace member replaces the workspace root package.
+        let (package, lock) = dependencies_locks
+            .iter()
+            .find(|(package, _lock)| &package.name == package_name)
+            .map(|(package, lock)| (package.clone(), lock.clone()))
+            .ok_or_else(|| format_err!("Selected package not resolved: {}", package_name))?;
+        dependencies_locks.remove(&package);
+        primary_package = Some(package);
+        primary_package_lock = lock;
+    } else if let Some(primary_package) = &primary_package {
+        if options.stream_locks.is_some() {
+            // Streamed locks are not returned: only the primary package lock was kept.
+            dependencies_locks.remove(primary_package);
+        }
+        let mut details = crate::registries::crates::package_details_from_toml(&manifest_path)?;
+        details.dependencies = packages_details
+            .get(primary_package)
+            .and_then(|details| details.dependencies.clone());
+        packages_details.insert(primary_package.clone(), details);
+    }
+    super::common::process_dependencies_locks(
+        args,
+        session,
+        &mut dependencies_locks,
+        &mut packages_details,
+    )?;
+    let mut lock_statuses = dependencies.lock_statuses;
+    super::common::retain_lock_statuses(
+        args,
+        &mut lock_statuses,
+        dependencies_locks.keys().chain(primary_package.as_ref()),
+    );
+    super::common::verify_signatures(
+        args,
+        &dependencies.raw_locks,
+        &dependencies.signatures,
+        &mut packages_details,
+    )?;
+
+    Ok(crate::resolution::Resolution {
+        locks: ProjectDependenciesLocks {
+            project_path,
+            package_locks: openfare_lib::package::PackageLocks {
+                primary_package,
+                primary_package_lock,
+                dependencies_locks,
+            },
         },
+        packages_details,
+        raw_locks: dependencies.raw_locks,
+        signatures: dependencies.signatures,
+        lock_statuses,
+        diagnostics: super::common::diagnostics(session),
     })
 }
+
+/// Returns the project dependency definitions file: `--manifest-path`, otherwise the file
+/// identified walking up from the canonical working directory.
+///
+/// Returns `None` with a warning if no file is found, unless a manifest is required.
+pub fn find_manifest(
+    working_directory: &std::path::Path,
+    args: &super::arguments::Arguments,
+    session: &crate::session::Session,
+) -> Result<Option<std::path::PathBuf>> {
+    if let Some(manifest_path) = &args.manifest_path {
+        let manifest_path = working_directory.join(manifest_path);
+        check_custom_manifest(&manifest_path)?;
+        return Ok(Some(manifest_path));
+    }
+
+    // Identify all dependency definition files.
+    let dependency_files = crate::registries::crates::identify_dependency_files(working_directory)?
+        .unwrap_or_default();
+    if let Some(dependency_file) =
+        crate::registries::crates::select_dependency_file(dependency_files, &args.dependency_file)?
+    {
+        return Ok(Some(dependency_file.path));
+    }
+    let message = format!(
+        "No Cargo.toml or Cargo.lock found walking up from {}",
+        working_directory.display()
+    );
+    if args.require_manifest {
+        return Err(format_err!(message));
+    }
+    session.warn(
+        crate::resolution::WarningCategory::MissingManifest,
+        None,
+        format!("{}. Skipping resolution.", message),
+    );
+    Ok(None)
+}
+
+/// Cache the crate archives listed in a standalone Cargo.lock, returning diagnostics without
+/// locks.
+fn prefetch(
+    args: &super::arguments::Arguments,
+    cargo_lock_path: &std::path::Path,
+    project_path: &std::path::Path,
+    session: &crate::session::Session,
+) -> Result<crate::resolution::Resolution<ProjectDependenciesLocks>> {
+    crate::registries::crates::prefetch_archives(
+        cargo_lock_path,
+        &args.resolution_options()?,
+        session,
+    )?;
+    let diagnostics = super::common::diagnostics(session);
+    log::info!(
+        "Prefetched {} crate archives.",
+        diagnostics.fetched_archives
+    );
+    Ok(crate::resolution::Resolution {
+        locks: ProjectDependenciesLocks {
+            project_path: project_path.to_path_buf(),
+            package_locks: Default::default(),
+        },
+        diagnostics,
+        ..Default::default()
+    })
+}
+
+/// Write the resolved locks snapshot into the project directory.
+fn write_resolved(
+    resolution: &crate::resolution::Resolution<ProjectDependenciesLocks>,
+) -> Result<()> {
+    let project_path = &resolution.locks.project_path;
+    if project_path.as_os_str().is_empty() {
+        log::warn!("No project resolved, resolved locks not written.");
+        return Ok(());
+    }
+    let snapshot = crate::resolution::Resolution {
+        locks: resolution.locks.package_locks.clone(),
+        raw_locks: resolution.raw_locks.clone(),
+        ..Default::default()
+    };
+    let path = crate::bundle::write_resolved(project_path, &snapshot)?;
+    log::info!("Wrote resolved locks: {}", path.display());
+    Ok(())
+}
+
+/// Check that a manifest given via `--manifest-path` exists and describes a package.
+fn check_custom_manifest(manifest_path: &std::path::Path) -> Result<()> {
+    if !manifest_path.is_file() {
+        return Err(format_err!(
+            "Manifest not found: {}",
+            manifest_path.display()
+        ));
+    }
+    // As with cargo, manifest file names are fixed for path dependency resolution.
+    match crate::registries::crates::DependencyFileType::from_path(manifest_path) {
+        Some(crate::registries::crates::DependencyFileType::CargoToml) => {}
+        Some(crate::registries::crates::DependencyFileType::CargoLock) => return Ok(()),
+        None => {
+            return Err(format_err!(
+                "Manifest path must point to a Cargo.toml or Cargo.lock file: {}",
+                manifest_path.display()
+            ));
+        }
+    }
+    crate::registries::crates::package_from_toml(manifest_path)?.ok_or_else(|| {
+        format_err!(
+            "Manifest does not define a package: {}",
+            manifest_path.display()
+        )
+    })?;
+    Ok(())
+}
+
+/// Returns project dependencies locks from a standalone Cargo.lock.
+///
+/// Without a manifest there is no primary package.
+fn lockfile_dependencies_locks(
+    args: &super::arguments::Arguments,
+    cargo_lock_path: &std::path::Path,
+    project_path: &std::path::Path,
+    session: &crate::session::Session,
+) -> Result<crate::resolution::Resolution<ProjectDependenciesLocks>> {
+    let dependencies = crate::registries::crates::dependencies_locks_from_cargo_lock(
+        cargo_lock_path,
+        &args.resolution_options()?,
+        session,
+    )?;
+    let mut dependencies_locks = dependencies.locks;
+    let mut packages_details = dependencies.packages_details;
+    super::common::process_dependencies_locks(
+        args,
+        session,
+        &mut dependencies_locks,
+        &mut packages_details,
+    )?;
+    let mut lock_statuses = dependencies.lock_statuses;
+    super::common::retain_lock_statuses(args, &mut lock_statuses, dependencies_locks.keys());
+    super::common::verify_signatures(
+        args,
+        &dependencies.raw_locks,
+        &dependencies.signatures,
+        &mut packages_details,
+    )?;
+
+    Ok(crate::resolution::Resolution {
+        locks: ProjectDependenciesLocks {
+            project_path: project_path.to_path_buf(),
+            package_locks: openfare_lib::package::PackageLocks {
+                primary_package: None,
+                primary_package_lock: crate::registries::crates::get_lock(project_path)?,
+                dependencies_locks,
+            },
+        },
+        packages_details,
+        raw_locks: dependencies.raw_locks,
+        signatures: dependencies.signatures,
+        lock_statuses,
+        diagnostics: super::common::diagnostics(session),
+    })
+}
+
+/// Returns the primary package and its lock only, without resolving dependencies.
+pub fn primary_package_locks(
+    manifest_path: &std::path::Path,
+    session: &crate::session::Session,
+) -> Result<openfare_lib::package::PackageLocks> {
+    Ok(openfare_lib::package::PackageLocks {
+        primary_package: crate::registries::crates::package_from_toml(manifest_path)?,
+        primary_package_lock: primary_package_lock(manifest_path, session)?,
+        ..Default::default()
+    })
+}
+
+/// Returns the project OpenFare lock.
+///
+/// The standalone lock file is preferred over the manifest `[package.metadata.openfare]`
+/// table. A warning is raised if both are present and differ.
+fn primary_package_lock(
+    manifest_path: &std::path::Path,
+    session: &crate::session::Session,
+) -> Result<Option<openfare_lib::lock::Lock>> {
+    let project_path = manifest_path.parent().ok_or_else(|| {
+        format_err!(
+            "Failed to derive parent directory from dependency file path: {}",
+            manifest_path.display()
+        )
+    })?;
+    let lock = crate::registries::crates::get_lock(project_path)?;
+    let embedded_lock = crate::registries::crates::get_embedded_lock(manifest_path)?;
+    if lock.is_some()
+        && embedded_lock.is_some()
+        && !crate::locks::locks_equal(&lock, &embedded_lock)
+    {
+        session.warn(
+            crate::resolution::WarningCategory::ProjectLock,
+            None,
+            format!(
+                "Project {} and [package.metadata.openfare] differ, using {}: {}",
+                openfare_lib::lock::FILE_NAME,
+                openfare_lib::lock::FILE_NAME,
+                project_path.display()
+            ),
+        );
+    }
+    Ok(lock.or(embedded_lock))
+}
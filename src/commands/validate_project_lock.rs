@@ -0,0 +1,90 @@
+use anyhow::{format_err, Result};
+
+/// Validate the project OpenFare lock against resolved project dependencies.
+///
+/// Reports lock package references which do not match a resolved dependency. The lock used
+/// for the project is validated: the lock file, otherwise the manifest
+/// `[package.metadata.openfare]` table. References are checked against every resolved
+/// dependency: arguments filtering dependencies (e.g. `--filter`, `--since`) do not apply.
+pub fn validate_project_lock(
+    extension: &crate::RsExtension,
+    working_directory: &std::path::Path,
+    extension_args: &[String],
+) -> Result<crate::locks::LockValidation> {
+    let args = super::arguments::Arguments::from_extension_args(extension_args)?;
+    super::common::configure_logging(&args);
+    let session = crate::session::Session::new(extension.progress());
+    let working_directory = &crate::registries::crates::canonicalize_directory(working_directory)?;
+
+    let manifest_path =
+        match super::project_dependencies_locks::find_manifest(working_directory, &args, &session)?
+        {
+            Some(manifest_path) => manifest_path,
+            None => return Ok(crate::locks::LockValidation::default()),
+        };
+    let project_path = manifest_path.parent().ok_or_else(|| {
+        format_err!(
+            "Failed to derive parent directory from dependency file path: {}",
+            manifest_path.display()
+        )
+    })?;
+    let is_cargo_lock = crate::registries::crates::DependencyFileType::from_path(&manifest_path)
+        == Some(crate::registries::crates::DependencyFileType::CargoLock);
+
+    let lock_json = match crate::registries::crates::get_raw_lock(project_path)? {
+        Some(raw_lock) => Some(crate::registries::crates::raw_lock_json(&raw_lock.bytes)?),
+        None if !is_cargo_lock => {
+            crate::registries::crates::get_embedded_lock_json(&manifest_path)?
+        }
+        None => None,
+    };
+    let lock_json = match lock_json {
+        Some(lock_json) => lock_json,
+        None => {
+            log::warn!(
+                "No OpenFare lock found for project: {}",
+                project_path.display()
+            );
+            return Ok(crate::locks::LockValidation::default());
+        }
+    };
+
+    super::common::check_index_snapshot(&args, args.manifest_only && !is_cargo_lock)?;
+    let options = crate::registries::crates::ResolutionOptions {
+        stream_locks: None,
+        unchanged_packages: Default::default(),
+        ..args.resolution_options()?
+    };
+    let dependencies = if is_cargo_lock {
+        crate::registries::crates::dependencies_locks_from_cargo_lock(
+            &manifest_path,
+            &options,
+            &session,
+        )?
+    } else if args.manifest_only {
+        crate::registries::crates::dependencies_locks_from_manifest(
+            &manifest_path,
+            &options,
+            &session,
+        )?
+    } else {
+        crate::registries::crates::dependencies_locks(&manifest_path, &options, &session)?
+    };
+
+    let dangling_references: Vec<String> = crate::locks::package_references(&lock_json)
+        .into_iter()
+        .filter(|reference| {
+            !crate::locks::is_resolved_reference(reference, dependencies.locks.keys())
+        })
+        .collect();
+    for reference in &dangling_references {
+        log::warn!(
+            "Project OpenFare lock references unresolved package: {}",
+            reference
+        );
+    }
+    Ok(crate::locks::LockValidation {
+        lock_found: true,
+        dangling_references,
+    })
+}
@@ -0,0 +1,65 @@
+use anyhow::{format_err, Result};
+
+/// Resolve dependencies locks for each workspace member.
+///
+/// Members are resolved together so that shared dependencies are downloaded and read
+/// once. Results are keyed by member package.
+pub fn workspace_dependencies_locks(
+    extension: &crate::RsExtension,
+    working_directory: &std::path::Path,
+    extension_args: &[String],
+) -> Result<
+    crate::resolution::Resolution<
+        std::collections::BTreeMap<
+            openfare_lib::package::Package,
+            openfare_lib::package::PackageLocks,
+        >,
+    >,
+> {
+    let args = super::arguments::Arguments::from_extension_args(extension_args)?;
+    super::common::configure_logging(&args);
+    super::common::clear_cache(&args)?;
+    let session = crate::session::Session::new(extension.progress());
+    let working_directory = &crate::registries::crates::canonicalize_directory(working_directory)?;
+
+    let manifest_path = match &args.manifest_path {
+        Some(manifest_path) => working_directory.join(manifest_path),
+        None => working_directory
+            .join(crate::registries::crates::DependencyFileType::CargoToml.file_name()),
+    };
+    if !manifest_path.is_file() {
+        return Err(format_err!(
+            "Manifest not found: {}",
+            manifest_path.display()
+        ));
+    }
+
+    super::common::check_index_snapshot(&args, false)?;
+
+    let mut resolution = crate::registries::crates::workspace_members_locks(
+        &manifest_path,
+        &args.resolution_options()?,
+        &session,
+    )?;
+    super::common::process_members_dependencies_locks(
+        &args,
+        &session,
+        &mut resolution.locks,
+        &mut resolution.packages_details,
+    )?;
+    super::common::retain_lock_statuses(
+        &args,
+        &mut resolution.lock_statuses,
+        resolution.locks.iter().flat_map(|(member, package_locks)| {
+            std::iter::once(member).chain(package_locks.dependencies_locks.keys())
+        }),
+    );
+    super::common::verify_signatures(
+        &args,
+        &resolution.raw_locks,
+        &resolution.signatures,
+        &mut resolution.packages_details,
+    )?;
+    resolution.diagnostics = super::common::diagnostics(&session);
+    Ok(resolution)
+}
@@ -1 +1,154 @@
-pub static HTTP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
+pub static HTTP_USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (+",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")",
+);
+
+/// Returns HTTP user agent.
+///
+/// Can be overridden using the OPENFARE_RS_USER_AGENT environment variable.
+pub fn http_user_agent() -> String {
+    std::env::var("OPENFARE_RS_USER_AGENT")
+        .ok()
+        .filter(|user_agent| !user_agent.trim().is_empty())
+        .unwrap_or_else(|| HTTP_USER_AGENT.to_string())
+}
+
+/// Returns true if the given pattern contains glob wildcards (`*` or `?`).
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(|c| c == '*' || c == '?')
+}
+
+/// Match text against a glob pattern: `*` matches any sequence, `?` any single character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Pattern and text positions to resume from after the last `*`.
+    let mut backtrack = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Returns the cargo home directory.
+///
+/// Uses the `CARGO_HOME` environment variable if set, otherwise `~/.cargo`. A relative
+/// `CARGO_HOME` is resolved against the current working directory, as cargo does.
+pub fn cargo_home() -> anyhow::Result<std::path::PathBuf> {
+    let current_directory = std::env::current_dir()?;
+    if let Some(cargo_home) = std::env::var_os("CARGO_HOME").filter(|path| !path.is_empty()) {
+        return Ok(current_directory.join(cargo_home));
+    }
+    let home_directory = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| {
+            anyhow::format_err!("Failed to find home directory. Please set CARGO_HOME.")
+        })?;
+    Ok(std::path::PathBuf::from(home_directory).join(".cargo"))
+}
+
+/// Temporary directory name prefix.
+const TEMP_DIR_PREFIX: &str = "openfare_rs";
+
+/// Returns the extension cache directory: `$XDG_CACHE_HOME/openfare-rs`, otherwise
+/// `~/.cache/openfare-rs`.
+pub fn cache_directory() -> anyhow::Result<std::path::PathBuf> {
+    if let Some(cache_home) = std::env::var_os("XDG_CACHE_HOME").filter(|path| !path.is_empty()) {
+        return Ok(std::path::PathBuf::from(cache_home).join("openfare-rs"));
+    }
+    let home_directory = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| anyhow::format_err!("Failed to find home directory."))?;
+    Ok(std::path::PathBuf::from(home_directory)
+        .join(".cache")
+        .join("openfare-rs"))
+}
+
+/// Create a temporary directory, removed when dropped or on forced exit after an interrupt.
+///
+/// Created within OPENFARE_RS_TMPDIR if set. Otherwise the system temporary directory is
+/// used, falling back to the cache directory if creation fails there.
+pub fn temp_dir() -> anyhow::Result<crate::interrupt::TempDir> {
+    Ok(crate::interrupt::TempDir::register(create_temp_dir()?))
+}
+
+fn create_temp_dir() -> anyhow::Result<tempdir::TempDir> {
+    if let Some(tmp_dir) = std::env::var_os("OPENFARE_RS_TMPDIR").filter(|path| !path.is_empty()) {
+        let tmp_dir = std::path::PathBuf::from(tmp_dir);
+        return tempdir::TempDir::new_in(&tmp_dir, TEMP_DIR_PREFIX).map_err(|error| {
+            anyhow::format_err!(
+                "Failed to create temporary directory in OPENFARE_RS_TMPDIR {}: {}",
+                tmp_dir.display(),
+                error
+            )
+        });
+    }
+
+    let system_tmp_dir = std::env::temp_dir();
+    let error = match tempdir::TempDir::new_in(&system_tmp_dir, TEMP_DIR_PREFIX) {
+        Ok(tmp_dir) => return Ok(tmp_dir),
+        Err(error) => error,
+    };
+    log::warn!(
+        "Failed to create temporary directory in {}: {}. Trying cache directory.",
+        system_tmp_dir.display(),
+        error
+    );
+    let fallback = cache_directory().and_then(|cache_directory| {
+        let tmp_root = cache_directory.join("tmp");
+        std::fs::create_dir_all(&tmp_root)?;
+        Ok(tempdir::TempDir::new_in(&tmp_root, TEMP_DIR_PREFIX)?)
+    });
+    fallback.map_err(|fallback_error| {
+        anyhow::format_err!(
+            "Failed to create temporary directory in {} ({}) or the cache directory ({}). \
+            Set OPENFARE_RS_TMPDIR to a writable directory.",
+            system_tmp_dir.display(),
+            error,
+            fallback_error
+        )
+    })
+}
+
+/// Returns the cargo credentials file path.
+///
+/// Prefers `credentials.toml`, falling back to the legacy `credentials` file name.
+pub fn cargo_credentials_path() -> anyhow::Result<std::path::PathBuf> {
+    let cargo_home = cargo_home()?;
+    let path = cargo_home.join("credentials.toml");
+    let legacy_path = cargo_home.join("credentials");
+    if !path.is_file() && legacy_path.is_file() {
+        return Ok(legacy_path);
+    }
+    Ok(path)
+}
+
+/// Returns a new cargo configuration which uses the resolved cargo home directory.
+pub fn cargo_config() -> anyhow::Result<cargo::util::config::Config> {
+    let cargo_home = cargo_home()?;
+    log::debug!("Using cargo home: {}", cargo_home.display());
+    Ok(cargo::util::config::Config::new(
+        cargo::core::Shell::new(),
+        std::env::current_dir()?,
+        cargo_home,
+    ))
+}
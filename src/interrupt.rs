@@ -0,0 +1,111 @@
+//! Interruption (Ctrl-C) handling.
+//!
+//! The first interrupt requests cancellation: in-flight work stops at its next check and
+//! temporary directories are removed as their owners are dropped. A second interrupt, or
+//! work failing to stop within `FORCED_EXIT_DELAY`, removes temporary directories and exits
+//! immediately.
+use anyhow::Result;
+
+/// Process exit status after an interrupt (128 + SIGINT).
+pub const EXIT_STATUS: i32 = 130;
+
+/// Time allowed for in-flight work to stop before exiting regardless.
+const FORCED_EXIT_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+static INTERRUPTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Temporary directories removed on forced exit.
+static TEMP_DIRS: once_cell::sync::Lazy<std::sync::Mutex<Vec<std::path::PathBuf>>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(Vec::new()));
+
+/// Returns the registered temporary directories, locked.
+///
+/// A panic while the lock was held leaves the list itself intact, so poisoning is ignored.
+fn temp_dirs() -> std::sync::MutexGuard<'static, Vec<std::path::PathBuf>> {
+    TEMP_DIRS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Temporary directory removed when dropped or on forced exit after an interrupt.
+#[derive(Debug)]
+pub struct TempDir {
+    /// Taken when dropped.
+    tmp_dir: Option<tempdir::TempDir>,
+}
+
+impl TempDir {
+    /// Register a temporary directory for removal on forced exit.
+    pub fn register(tmp_dir: tempdir::TempDir) -> Self {
+        temp_dirs().push(tmp_dir.path().to_path_buf());
+        Self {
+            tmp_dir: Some(tmp_dir),
+        }
+    }
+
+    /// Returns the temporary directory path.
+    pub fn path(&self) -> &std::path::Path {
+        self.tmp_dir
+            .as_ref()
+            .expect("temporary directory taken before drop")
+            .path()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if let Some(tmp_dir) = self.tmp_dir.take() {
+            let path = tmp_dir.path().to_path_buf();
+            if let Err(error) = tmp_dir.close() {
+                log::debug!(
+                    "Failed to remove temporary directory {}: {}",
+                    path.display(),
+                    error
+                );
+            }
+            temp_dirs().retain(|registered| *registered != path);
+        }
+    }
+}
+
+/// Returns the temporary directories currently registered for removal on forced exit.
+pub fn registered_temp_dirs() -> Vec<std::path::PathBuf> {
+    temp_dirs().clone()
+}
+
+/// Install the process interrupt handler. Intended for the extension binary only.
+pub fn install_handler() -> Result<()> {
+    ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            exit();
+        }
+        eprintln!("Interrupted, stopping. Press Ctrl-C again to exit immediately.");
+        std::thread::spawn(|| {
+            std::thread::sleep(FORCED_EXIT_DELAY);
+            exit();
+        });
+    })?;
+    Ok(())
+}
+
+/// Returns true once an interrupt was received.
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Returns an error once an interrupt was received, cancelling the calling work.
+pub fn check() -> Result<()> {
+    if is_interrupted() {
+        return Err(anyhow::format_err!("Interrupted."));
+    }
+    Ok(())
+}
+
+/// Remove registered temporary directories and exit.
+fn exit() -> ! {
+    for path in temp_dirs().iter().filter(|path| path.exists()) {
+        let _ = std::fs::remove_dir_all(&path);
+    }
+    eprintln!("Interrupted.");
+    std::process::exit(EXIT_STATUS);
+}
@@ -1,14 +1,148 @@
 use anyhow::Result;
 
+pub mod advisories;
+mod archive;
+pub mod bundle;
+pub mod cache;
 mod commands;
 mod common;
+pub mod interrupt;
+pub mod locks;
+pub mod logging;
+pub mod pacing;
+pub mod progress;
 mod registries;
+pub mod resolution;
+pub mod self_test;
+mod session;
+pub mod signatures;
+pub mod tree;
+
+pub use registries::crates::{get_raw_lock, get_raw_lock_from_archive};
 
 #[derive(Clone, Debug)]
 pub struct RsExtension {
     name_: String,
     registry_host_names_: Vec<String>,
-    version_: String,
+    progress_: std::sync::Arc<dyn progress::Progress>,
+}
+
+impl RsExtension {
+    /// Set handler which receives progress updates during resolution.
+    pub fn with_progress(mut self, progress: std::sync::Arc<dyn progress::Progress>) -> Self {
+        self.progress_ = progress;
+        self
+    }
+
+    /// Handler which receives progress updates during resolution.
+    pub fn progress(&self) -> &dyn progress::Progress {
+        self.progress_.as_ref()
+    }
+
+    /// Resolve package dependencies locks with additional package details.
+    pub fn resolve_package(
+        &self,
+        package_name: &str,
+        package_version: &Option<&str>,
+        extension_args: &[String],
+    ) -> Result<
+        resolution::Resolution<
+            openfare_lib::extension::commands::package_dependencies_locks::PackageDependenciesLocks,
+        >,
+    > {
+        commands::package_dependencies_locks(self, package_name, package_version, extension_args)
+    }
+
+    /// Resolve project dependencies locks with additional package details.
+    pub fn resolve_project(
+        &self,
+        working_directory: &std::path::Path,
+        extension_args: &[String],
+    ) -> Result<
+        resolution::Resolution<
+            openfare_lib::extension::commands::project_dependencies_locks::ProjectDependenciesLocks,
+        >,
+    > {
+        commands::project_dependencies_locks(self, working_directory, extension_args)
+    }
+
+    /// Resolve project dependencies and return those without an OpenFare lock.
+    ///
+    /// Useful for reports asking maintainers to add an OpenFare lock.
+    pub fn project_packages_without_locks(
+        &self,
+        working_directory: &std::path::Path,
+        extension_args: &[String],
+    ) -> Result<Vec<openfare_lib::package::Package>> {
+        let resolution = self.resolve_project(working_directory, extension_args)?;
+        Ok(locks::packages_without_locks(
+            &resolution.locks.package_locks.dependencies_locks,
+        ))
+    }
+
+    /// Check the environment: registry access, crate download and extraction, cache
+    /// writability and the embedded cargo library.
+    ///
+    /// Uses `self_test::DEFAULT_CRATE` unless a crate name is given.
+    pub fn self_test(&self, crate_name: Option<&str>) -> self_test::Report {
+        self_test::run(crate_name.unwrap_or(self_test::DEFAULT_CRATE))
+    }
+
+    /// Resolve dependencies locks for a git repository at the given revision.
+    ///
+    /// The repository is shallow cloned into a temporary directory which is removed afterwards.
+    pub fn resolve_git(
+        &self,
+        git_url: &str,
+        rev: &str,
+        extension_args: &[String],
+    ) -> Result<
+        resolution::Resolution<
+            openfare_lib::extension::commands::project_dependencies_locks::ProjectDependenciesLocks,
+        >,
+    > {
+        commands::git_dependencies_locks(self, git_url, rev, extension_args)
+    }
+
+    /// Validate the project's own OpenFare lock against its resolved dependencies.
+    pub fn validate_project_lock(
+        &self,
+        working_directory: &std::path::Path,
+        extension_args: &[String],
+    ) -> Result<locks::LockValidation> {
+        commands::validate_project_lock(self, working_directory, extension_args)
+    }
+
+    /// Resolve dependencies locks for each member of the workspace at the working directory.
+    ///
+    /// Returns package locks keyed by workspace member.
+    pub fn resolve_workspace_members(
+        &self,
+        working_directory: &std::path::Path,
+        extension_args: &[String],
+    ) -> Result<
+        resolution::Resolution<
+            std::collections::BTreeMap<
+                openfare_lib::package::Package,
+                openfare_lib::package::PackageLocks,
+            >,
+        >,
+    > {
+        commands::workspace_dependencies_locks(self, working_directory, extension_args)
+    }
+
+    /// Resolve dependencies locks of the project at the working directory for each feature
+    /// configuration given using `--feature-set` extension arguments.
+    ///
+    /// Returns dependencies locks keyed by feature configuration.
+    pub fn resolve_feature_matrix(
+        &self,
+        working_directory: &std::path::Path,
+        extension_args: &[String],
+    ) -> Result<resolution::Resolution<std::collections::BTreeMap<String, locks::DependenciesLocks>>>
+    {
+        commands::feature_matrix_dependencies_locks(self, working_directory, extension_args)
+    }
 }
 
 impl openfare_lib::extension::FromLib for RsExtension {
@@ -20,7 +154,7 @@ impl openfare_lib::extension::FromLib for RsExtension {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
-            version_: format!("CARGO_PKG_VERSION: {}", env!("CARGO_PKG_VERSION"),),
+            progress_: std::sync::Arc::new(progress::NoProgress),
         }
     }
 }
@@ -35,7 +169,7 @@ impl openfare_lib::extension::Extension for RsExtension {
     }
 
     fn version(&self) -> String {
-        self.version_.clone()
+        version()
     }
 
     fn package_dependencies_locks(
@@ -46,12 +180,9 @@ impl openfare_lib::extension::Extension for RsExtension {
     ) -> Result<
         openfare_lib::extension::commands::package_dependencies_locks::PackageDependenciesLocks,
     > {
-        commands::package_dependencies_locks(
-            &self,
-            &package_name,
-            &package_version,
-            &extension_args,
-        )
+        Ok(self
+            .resolve_package(package_name, package_version, extension_args)?
+            .locks)
     }
 
     fn project_dependencies_locks(
@@ -61,6 +192,90 @@ impl openfare_lib::extension::Extension for RsExtension {
     ) -> Result<
         openfare_lib::extension::commands::project_dependencies_locks::ProjectDependenciesLocks,
     > {
-        commands::project_dependencies_locks(&working_directory, &extension_args)
+        Ok(self
+            .resolve_project(working_directory, extension_args)?
+            .locks)
     }
 }
+
+/// Returns package locks for an already extracted crate or project directory.
+///
+/// Skips the package download. Dependencies are resolved using cargo as usual.
+pub fn locks_from_directory(
+    directory: &std::path::Path,
+) -> Result<openfare_lib::package::PackageLocks> {
+    let session = session::Session::new(&progress::NoProgress);
+    Ok(commands::locks_from_directory(
+        directory,
+        &registries::crates::ResolutionOptions::default(),
+        &session,
+    )?
+    .locks)
+}
+
+/// Returns dependencies locks for the packages listed in `cargo metadata` JSON output.
+///
+/// Skips dependency resolution, for callers which already have cargo metadata.
+pub fn locks_from_metadata(metadata_json: &str) -> Result<locks::DependenciesLocks> {
+    let session = session::Session::new(&progress::NoProgress);
+    Ok(registries::crates::dependencies_locks_from_metadata(
+        metadata_json,
+        &registries::crates::ResolutionOptions::default(),
+        &session,
+    )?
+    .locks)
+}
+
+/// Returns the OpenFare lock file shipped in a published crate version, if any.
+///
+/// Only downloads the crate archive: the crate is neither extracted nor resolved.
+pub fn crate_lock(
+    package_name: &str,
+    package_version: &str,
+) -> Result<Option<openfare_lib::lock::Lock>> {
+    let session = session::Session::new(&progress::NoProgress);
+    registries::crates::get_published_lock(package_name, package_version, &session)
+}
+
+/// Returns true if a published crate version ships an OpenFare lock file.
+pub fn crate_has_lock(package_name: &str, package_version: &str) -> Result<bool> {
+    Ok(crate_lock(package_name, package_version)?.is_some())
+}
+
+/// Returns the package and its OpenFare lock for a crate or project directory.
+///
+/// Fast path which skips dependency resolution: dependencies locks are empty.
+pub fn primary_package_locks(
+    directory: &std::path::Path,
+) -> Result<openfare_lib::package::PackageLocks> {
+    let session = session::Session::new(&progress::NoProgress);
+    commands::primary_package_locks(
+        &directory.join(registries::crates::DependencyFileType::CargoToml.file_name()),
+        &session,
+    )
+}
+
+/// Returns extension version details.
+///
+/// Includes the embedded cargo library version and offline mode state since both
+/// determine dependency resolution behaviour. Offline mode is read from cargo
+/// configuration (`net.offline` or `CARGO_NET_OFFLINE`) when the version is requested.
+fn version() -> String {
+    let offline = match cargo_offline() {
+        Ok(offline) => offline.to_string(),
+        Err(_) => "unknown".to_string(),
+    };
+    format!(
+        "CARGO_PKG_VERSION: {}; cargo: {}; offline: {}",
+        env!("CARGO_PKG_VERSION"),
+        cargo::version(),
+        offline
+    )
+}
+
+/// Returns true if cargo is configured to run offline.
+fn cargo_offline() -> Result<bool> {
+    let mut config = common::cargo_config()?;
+    config.configure(0, false, None, false, false, false, &None, &[], &[])?;
+    Ok(config.offline())
+}
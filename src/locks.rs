@@ -0,0 +1,354 @@
+/// Dependencies and their OpenFare locks.
+pub type DependenciesLocks =
+    std::collections::BTreeMap<openfare_lib::package::Package, Option<openfare_lib::lock::Lock>>;
+
+/// OpenFare lock file contents alongside the parsed lock.
+#[derive(Debug, Clone)]
+pub struct RawLock {
+    /// Lock file bytes as published (decompressed if gzip compressed).
+    pub bytes: Vec<u8>,
+    pub lock: openfare_lib::lock::Lock,
+
+    /// Detached signature file bytes as published (e.g. `OpenFare.lock.sig`), if any.
+    pub signature: Option<Vec<u8>>,
+}
+
+/// Returns the hex encoded SHA-256 hash of OpenFare lock file bytes.
+pub fn lock_hash(bytes: &[u8]) -> String {
+    use sha2::Digest;
+    sha2::Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Differences between two dependencies locks sets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocksDiff {
+    /// Packages present only in the new set.
+    pub added: std::collections::BTreeSet<openfare_lib::package::Package>,
+
+    /// Packages present only in the old set.
+    pub removed: std::collections::BTreeSet<openfare_lib::package::Package>,
+
+    /// Packages present in both sets with differing locks.
+    pub changed: std::collections::BTreeSet<openfare_lib::package::Package>,
+}
+
+impl LocksDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare dependencies locks from two resolutions (e.g. before and after a dependency update).
+pub fn diff(old: &DependenciesLocks, new: &DependenciesLocks) -> LocksDiff {
+    let mut locks_diff = LocksDiff::default();
+    for (package, new_lock) in new {
+        match old.get(package) {
+            Some(old_lock) => {
+                if !locks_equal(old_lock, new_lock) {
+                    locks_diff.changed.insert(package.clone());
+                }
+            }
+            None => {
+                locks_diff.added.insert(package.clone());
+            }
+        }
+    }
+    for package in old.keys() {
+        if !new.contains_key(package) {
+            locks_diff.removed.insert(package.clone());
+        }
+    }
+    locks_diff
+}
+
+/// Compare locks by their serialized form (`Lock` does not implement `PartialEq`).
+pub fn locks_equal(
+    a: &Option<openfare_lib::lock::Lock>,
+    b: &Option<openfare_lib::lock::Lock>,
+) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Package name and version.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PackageVersion {
+    pub name: String,
+    pub version: String,
+}
+
+/// Summary of OpenFare lock coverage across dependencies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Coverage {
+    pub total: usize,
+    pub with_locks: usize,
+    pub without_locks: usize,
+
+    /// Packages without an OpenFare lock, ordered by name and version.
+    pub uncovered: Vec<PackageVersion>,
+}
+
+/// Summarize lock coverage.
+pub fn coverage(dependencies_locks: &DependenciesLocks) -> Coverage {
+    let uncovered: Vec<_> = dependencies_locks
+        .iter()
+        .filter(|(_package, lock)| lock.is_none())
+        .map(|(package, _lock)| PackageVersion {
+            name: package.name.clone(),
+            version: package.version.clone(),
+        })
+        .collect();
+    Coverage {
+        total: dependencies_locks.len(),
+        with_locks: dependencies_locks.len() - uncovered.len(),
+        without_locks: uncovered.len(),
+        uncovered,
+    }
+}
+
+/// Returns packages without an OpenFare lock: the inverse of the with-locks-only filter.
+pub fn packages_without_locks(
+    dependencies_locks: &DependenciesLocks,
+) -> Vec<openfare_lib::package::Package> {
+    dependencies_locks
+        .iter()
+        .filter(|(_package, lock)| lock.is_none())
+        .map(|(package, _lock)| package.clone())
+        .collect()
+}
+
+/// Dependencies locks split by dependency kind.
+#[derive(Debug, Clone, Default)]
+pub struct KindsLocks {
+    pub runtime: DependenciesLocks,
+    pub build: DependenciesLocks,
+    pub dev: DependenciesLocks,
+}
+
+/// Split dependencies locks by dependency kind (see `PackageDetails::kind`).
+///
+/// Packages of unknown kind (e.g. read from a standalone Cargo.lock) are runtime
+/// dependencies.
+pub fn separate_kinds(
+    dependencies_locks: &DependenciesLocks,
+    packages_details: &crate::resolution::PackagesDetails,
+) -> KindsLocks {
+    let mut kinds_locks = KindsLocks::default();
+    for (package, lock) in dependencies_locks {
+        let kind = packages_details
+            .get(package)
+            .and_then(|details| details.kind)
+            .unwrap_or(crate::resolution::DependencyKind::Normal);
+        let locks = match kind {
+            crate::resolution::DependencyKind::Normal => &mut kinds_locks.runtime,
+            crate::resolution::DependencyKind::Build => &mut kinds_locks.build,
+            crate::resolution::DependencyKind::Dev => &mut kinds_locks.dev,
+        };
+        locks.insert(package.clone(), lock.clone());
+    }
+    kinds_locks
+}
+
+/// Returns crate names present at more than one version, with their versions.
+///
+/// Versions are in ascending semver order. Each version may carry its own OpenFare terms.
+pub fn duplicate_versions(
+    dependencies_locks: &DependenciesLocks,
+) -> std::collections::BTreeMap<String, Vec<String>> {
+    let mut versions = std::collections::BTreeMap::<String, Vec<String>>::new();
+    for package in dependencies_locks.keys() {
+        versions
+            .entry(package.name.clone())
+            .or_default()
+            .push(package.version.clone());
+    }
+    versions.retain(|_name, versions| versions.len() > 1);
+    for versions in versions.values_mut() {
+        sort_versions(versions);
+    }
+    versions
+}
+
+/// Returns crate names resolved from more than one registry, with their versions per registry.
+///
+/// A crate whose versions come from different registries (e.g. crates.io and an alternate
+/// registry) may indicate a dependency confusion or a moved crate. Versions are in ascending
+/// semver order.
+pub fn registry_changes(
+    dependencies_locks: &DependenciesLocks,
+) -> std::collections::BTreeMap<String, std::collections::BTreeMap<String, Vec<String>>> {
+    let mut registries =
+        std::collections::BTreeMap::<String, std::collections::BTreeMap<String, Vec<String>>>::new(
+        );
+    for package in dependencies_locks.keys() {
+        registries
+            .entry(package.name.clone())
+            .or_default()
+            .entry(package.registry.clone())
+            .or_default()
+            .push(package.version.clone());
+    }
+    registries.retain(|_name, registries| registries.len() > 1);
+    for versions in registries
+        .values_mut()
+        .flat_map(|registries| registries.values_mut())
+    {
+        sort_versions(versions);
+    }
+    registries
+}
+
+/// Sort versions in ascending semver order. Invalid versions sort last, lexically.
+pub fn sort_versions(versions: &mut Vec<String>) {
+    versions.sort_by(
+        |a, b| match (semver::Version::parse(a), semver::Version::parse(b)) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+            (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+            (Err(_), Err(_)) => a.cmp(b),
+        },
+    );
+    versions.dedup();
+}
+
+/// Merge packages with the same name and version from different registries into one.
+///
+/// For source replacement setups (e.g. a crates.io mirror) where registry labels differ but
+/// the crates are identical. The package from `canonical_registry` is kept if present,
+/// otherwise the first by registry name. A lock found under any label is kept. Returns the
+/// removed packages.
+pub fn merge_registries(
+    dependencies_locks: &mut DependenciesLocks,
+    canonical_registry: &str,
+) -> Vec<openfare_lib::package::Package> {
+    let mut groups = std::collections::BTreeMap::<_, Vec<openfare_lib::package::Package>>::new();
+    for package in dependencies_locks.keys() {
+        groups
+            .entry((package.name.clone(), package.version.clone()))
+            .or_default()
+            .push(package.clone());
+    }
+
+    let mut removed = Vec::new();
+    for (_, mut packages) in groups
+        .into_iter()
+        .filter(|(_, packages)| packages.len() > 1)
+    {
+        packages.sort_by_key(|package| package.registry != canonical_registry);
+        let canonical = packages.remove(0);
+        let mut lock = dependencies_locks.get(&canonical).cloned().flatten();
+        for package in packages {
+            if let Some(Some(package_lock)) = dependencies_locks.remove(&package) {
+                lock = lock.or(Some(package_lock));
+            }
+            removed.push(package);
+        }
+        dependencies_locks.insert(canonical, lock);
+    }
+    removed
+}
+
+/// Output order of resolved packages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// By name, then ascending version.
+    Name,
+    /// By ascending version, then name.
+    Version,
+    /// Packages with an OpenFare lock first, then by name and version.
+    Locked,
+}
+
+impl std::str::FromStr for SortOrder {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "name" => Ok(Self::Name),
+            "version" => Ok(Self::Version),
+            "locked" => Ok(Self::Locked),
+            _ => Err(anyhow::format_err!(
+                "Unknown sort order: {} (expected name, version or locked)",
+                value
+            )),
+        }
+    }
+}
+
+/// Returns packages ordered for output.
+pub fn sorted_packages(
+    dependencies_locks: &DependenciesLocks,
+    sort_order: SortOrder,
+) -> Vec<&openfare_lib::package::Package> {
+    let version =
+        |package: &openfare_lib::package::Package| semver::Version::parse(&package.version).ok();
+    let mut packages: Vec<_> = dependencies_locks.keys().collect();
+    packages.sort_by(|a, b| {
+        let by_name = a.name.cmp(&b.name);
+        let by_version = version(a)
+            .cmp(&version(b))
+            .then_with(|| a.version.cmp(&b.version));
+        match sort_order {
+            SortOrder::Name => by_name.then(by_version),
+            SortOrder::Version => by_version.then(by_name),
+            SortOrder::Locked => {
+                let locked = |package| {
+                    dependencies_locks
+                        .get(package)
+                        .map(|lock| lock.is_some())
+                        .unwrap_or(false)
+                };
+                locked(b).cmp(&locked(a)).then(by_name).then(by_version)
+            }
+        }
+    });
+    packages
+}
+
+/// Result of validating a project's own OpenFare lock against its resolved dependencies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LockValidation {
+    /// Project OpenFare lock file found.
+    pub lock_found: bool,
+
+    /// Package references in the lock which do not match a resolved dependency.
+    pub dangling_references: Vec<String>,
+}
+
+/// Returns package references found in lock JSON.
+///
+/// The OpenFare lock format does not define package references: a project lists the packages
+/// its lock refers to in a top-level `packages` array, which OpenFare ignores. Its string
+/// elements are references of the form `name` or `name@version`. No other field is read.
+pub fn package_references(lock_json: &serde_json::Value) -> Vec<String> {
+    let mut references: Vec<String> = lock_json
+        .get("packages")
+        .and_then(|packages| packages.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|reference| reference.as_str())
+        .map(|reference| reference.to_string())
+        .collect();
+    references.sort();
+    references.dedup();
+    references
+}
+
+/// Returns true if the reference matches one of the packages.
+pub fn is_resolved_reference<'a>(
+    reference: &str,
+    mut packages: impl Iterator<Item = &'a openfare_lib::package::Package>,
+) -> bool {
+    let (name, version) = match reference.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (reference, None),
+    };
+    packages.any(|package| {
+        package.name == name
+            && version
+                .map(|version| package.version == version)
+                .unwrap_or(true)
+    })
+}
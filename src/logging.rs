@@ -0,0 +1,78 @@
+//! Logging for the extension binary.
+//!
+//! Log output is configured using the OPENFARE_RS_LOG environment variable (`env_logger`
+//! syntax, off by default). Verbosity extension arguments (`-v`, `-q`) are only known once
+//! a command parses its extension arguments: from then on they override the environment
+//! configuration.
+
+/// Log levels indexed by `LEVEL_OVERRIDE`.
+const LEVELS: [log::LevelFilter; 6] = [
+    log::LevelFilter::Off,
+    log::LevelFilter::Error,
+    log::LevelFilter::Warn,
+    log::LevelFilter::Info,
+    log::LevelFilter::Debug,
+    log::LevelFilter::Trace,
+];
+
+/// Index of the overriding log level in `LEVELS`, `usize::MAX` if not overridden.
+static LEVEL_OVERRIDE: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(usize::MAX);
+
+/// Logger applying the environment configuration unless the level is overridden.
+struct Logger {
+    /// Configured from OPENFARE_RS_LOG.
+    env: env_logger::Logger,
+    /// Logs every record, filtered by the overriding level.
+    all: env_logger::Logger,
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        match level_override() {
+            Some(level) => metadata.level() <= level,
+            None => self.env.enabled(metadata),
+        }
+    }
+
+    fn log(&self, record: &log::Record) {
+        match level_override() {
+            Some(level) if record.level() <= level => self.all.log(record),
+            Some(_) => {}
+            None => self.env.log(record),
+        }
+    }
+
+    fn flush(&self) {
+        self.env.flush();
+        self.all.flush();
+    }
+}
+
+/// Install the process logger. Intended for the extension binary only.
+pub fn init() -> Result<(), log::SetLoggerError> {
+    let env = env_logger::Env::new().filter_or("OPENFARE_RS_LOG", "off");
+    let logger = Logger {
+        env: env_logger::Builder::from_env(env).build(),
+        all: env_logger::Builder::new()
+            .filter_level(log::LevelFilter::Trace)
+            .build(),
+    };
+    log::set_boxed_logger(Box::new(logger))?;
+    log::set_max_level(log::LevelFilter::Trace);
+    Ok(())
+}
+
+/// Override the log level configured using OPENFARE_RS_LOG.
+pub fn set_level(level: log::LevelFilter) {
+    if let Some(index) = LEVELS.iter().position(|candidate| *candidate == level) {
+        LEVEL_OVERRIDE.store(index, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+/// Returns the overriding log level, if any.
+fn level_override() -> Option<log::LevelFilter> {
+    LEVELS
+        .get(LEVEL_OVERRIDE.load(std::sync::atomic::Ordering::SeqCst))
+        .copied()
+}
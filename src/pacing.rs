@@ -0,0 +1,48 @@
+//! Request pacing.
+//!
+//! Registries such as the crates.io API ask crawlers to keep to a request rate. Request
+//! slots are reserved atomically, so pacing holds across the threads of a run.
+
+/// Paces requests to a minimum interval between them. Shared across threads.
+#[derive(Debug, Default)]
+pub struct Pacer {
+    /// Earliest time (milliseconds since the Unix epoch) of the next request.
+    next_request_ms: std::sync::atomic::AtomicU64,
+}
+
+impl Pacer {
+    pub const fn new() -> Self {
+        Self {
+            next_request_ms: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Wait until a request is permitted, at least `interval` after the previous request.
+    ///
+    /// Returns the time waited.
+    pub fn wait(&self, interval: std::time::Duration) -> anyhow::Result<std::time::Duration> {
+        let interval = interval.as_millis() as u64;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis() as u64;
+
+        // Reserve the next free request slot.
+        let ordering = std::sync::atomic::Ordering::SeqCst;
+        let mut next = self.next_request_ms.load(ordering);
+        let slot = loop {
+            let slot = std::cmp::max(now, next);
+            match self
+                .next_request_ms
+                .compare_exchange(next, slot + interval, ordering, ordering)
+            {
+                Ok(_) => break slot,
+                Err(current) => next = current,
+            }
+        };
+        let delay = std::time::Duration::from_millis(slot - now);
+        if slot > now {
+            std::thread::sleep(delay);
+        }
+        Ok(delay)
+    }
+}
@@ -0,0 +1,39 @@
+/// Resolution step completed for a package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Package archive downloaded.
+    Download,
+    /// Package archive extracted.
+    Extract,
+    /// Package OpenFare lock read.
+    ReadLock,
+}
+
+/// Receives progress updates during resolution.
+///
+/// Implemented by applications embedding this library to drive progress indicators.
+pub trait Progress: std::fmt::Debug + Send + Sync {
+    /// Called once a step completes for a package. `completed` of `total` steps of this kind are done.
+    fn update(
+        &self,
+        step: Step,
+        package: &openfare_lib::package::Package,
+        completed: usize,
+        total: usize,
+    );
+}
+
+/// Progress handler which ignores all updates.
+#[derive(Debug, Clone, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn update(
+        &self,
+        _step: Step,
+        _package: &openfare_lib::package::Package,
+        _completed: usize,
+        _total: usize,
+    ) {
+    }
+}
@@ -0,0 +1,163 @@
+//! Local clone of a crates registry git index.
+//!
+//! Set OPENFARE_RS_INDEX_PATH to a checkout of the registry index (e.g. a clone of
+//! `https://github.com/rust-lang/crates.io-index`) to read crate versions from per-crate
+//! index files instead of the registry API. Crates absent from the index are queried from
+//! the registry as usual.
+use anyhow::{Context, Result};
+
+/// Crate version record: one JSON object per line of a per-crate index file.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IndexVersion {
+    pub name: String,
+    pub vers: String,
+    /// SHA-256 checksum of the crate archive, hex encoded.
+    #[serde(default)]
+    pub cksum: Option<String>,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+/// Index `config.json` file.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct IndexConfig {
+    /// Crate download URL, possibly containing `{crate}`-style markers.
+    #[serde(default)]
+    dl: Option<String>,
+}
+
+/// Returns the local index path set using OPENFARE_RS_INDEX_PATH, if any.
+pub fn index_path() -> Option<std::path::PathBuf> {
+    std::env::var_os("OPENFARE_RS_INDEX_PATH")
+        .filter(|path| !path.is_empty())
+        .map(std::path::PathBuf::from)
+}
+
+/// Returns the index file path of a crate relative to the index root.
+///
+/// Follows the registry index layout: `1/<name>`, `2/<name>`, `3/<c>/<name>` and
+/// `<ab>/<cd>/<name>` for longer names, using lowercase names.
+pub fn entry_path(package_name: &str) -> std::path::PathBuf {
+    let name = package_name.to_lowercase();
+    let chars: Vec<char> = name.chars().collect();
+    let segment = |range: std::ops::Range<usize>| chars[range].iter().collect::<String>();
+    let path = std::path::PathBuf::new();
+    match chars.len() {
+        0..=2 => path.join(chars.len().to_string()).join(&name),
+        3 => path.join("3").join(segment(0..1)).join(&name),
+        _ => path.join(segment(0..2)).join(segment(2..4)).join(&name),
+    }
+}
+
+/// Returns the versions of a crate listed in the local index.
+///
+/// Returns `None` if no local index is set or the crate is not in it.
+pub fn versions(package_name: &str) -> Result<Option<Vec<IndexVersion>>> {
+    let index_path = match index_path() {
+        Some(index_path) => index_path,
+        None => return Ok(None),
+    };
+    let path = index_path.join(entry_path(package_name));
+    if !path.is_file() {
+        log::debug!(
+            "Crate not found in local index, querying registry: {}",
+            package_name
+        );
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)
+        .context(format!("Failed to read index file: {}", path.display()))?;
+    let versions = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .context(format!("Failed to parse index file: {}", path.display()))
+        })
+        .collect::<Result<Vec<IndexVersion>>>()?;
+    Ok(Some(versions))
+}
+
+/// Returns the registry API crate entry derived from the local index, if the crate is in it.
+///
+/// Only the fields read from API responses are set: `crate.newest_version` (the highest
+/// non-yanked version) and `versions[].num`, `versions[].yanked` and `versions[].checksum`.
+pub fn registry_entry_json(package_name: &str) -> Result<Option<serde_json::Value>> {
+    let versions = match versions(package_name)? {
+        Some(versions) => versions,
+        None => return Ok(None),
+    };
+    let newest_version = versions
+        .iter()
+        .filter(|version| !version.yanked)
+        .filter_map(|version| semver::Version::parse(&version.vers).ok())
+        .max()
+        .map(|version| version.to_string());
+    log::debug!("Using local index entry: {}", package_name);
+    Ok(Some(serde_json::json!({
+        "crate": {"name": package_name, "newest_version": newest_version},
+        "versions": versions
+            .iter()
+            .map(|version| serde_json::json!({
+                "num": version.vers,
+                "yanked": version.yanked,
+                "checksum": version.cksum,
+            }))
+            .collect::<Vec<_>>(),
+    })))
+}
+
+/// Returns the crate download URL given by the local index `config.json`, if any.
+///
+/// The `dl` value is expanded as cargo does: `{crate}`, `{version}`, `{prefix}`,
+/// `{lowerprefix}` and `{sha256-checksum}` markers are replaced, and
+/// `/{crate}/{version}/download` is appended if there are none.
+pub fn download_url(package_name: &str, package_version: &str) -> Result<Option<String>> {
+    let index_path = match index_path() {
+        Some(index_path) => index_path,
+        None => return Ok(None),
+    };
+    let path = index_path.join("config.json");
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)
+        .context(format!("Failed to read index config: {}", path.display()))?;
+    let config: IndexConfig = serde_json::from_str(&contents)
+        .context(format!("Failed to parse index config: {}", path.display()))?;
+    let dl = match config.dl {
+        Some(dl) => dl.trim_end_matches('/').to_string(),
+        None => return Ok(None),
+    };
+
+    const MARKERS: &[&str] = &[
+        "{crate}",
+        "{version}",
+        "{prefix}",
+        "{lowerprefix}",
+        "{sha256-checksum}",
+    ];
+    if !MARKERS.iter().any(|marker| dl.contains(marker)) {
+        return Ok(Some(format!(
+            "{}/{}/{}/download",
+            dl, package_name, package_version
+        )));
+    }
+    let prefix = entry_path(package_name)
+        .parent()
+        .map(|prefix| prefix.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+    let checksum = versions(package_name)?
+        .unwrap_or_default()
+        .into_iter()
+        .find(|version| version.vers == package_version)
+        .and_then(|version| version.cksum)
+        .unwrap_or_default();
+    Ok(Some(
+        dl.replace("{crate}", package_name)
+            .replace("{version}", package_version)
+            .replace("{lowerprefix}", &prefix)
+            .replace("{prefix}", &prefix)
+            .replace("{sha256-checksum}", &checksum),
+    ))
+}
@@ -1,3 +1,93 @@
+use anyhow::Result;
+
 pub mod crates;
+pub mod index;
+pub mod snapshot;
 
 pub const HOST_NAMES: [&'static str; 1] = [crates::HOST_NAME];
+
+/// Package registry backend.
+///
+/// Provides what commands need from a registry independently of its protocol. Backends are
+/// selected by registry host name using `backend`, or by cargo source ID using
+/// `source_backend`.
+pub trait Registry {
+    /// Registry host name, recorded as the registry of its packages.
+    fn host_name(&self) -> &str;
+
+    /// Returns package from this registry.
+    fn package(&self, package_name: &str, package_version: &str) -> openfare_lib::package::Package {
+        openfare_lib::package::Package {
+            name: package_name.to_string(),
+            version: package_version.to_string(),
+            registry: self.host_name().to_string(),
+        }
+    }
+
+    /// Returns the latest published version of a package, if any.
+    ///
+    /// With an index snapshot, only versions published at the snapshot time are considered.
+    fn latest_version(
+        &self,
+        package_name: &str,
+        index_snapshot: Option<&snapshot::IndexSnapshot>,
+        session: &crate::session::Session,
+    ) -> Result<Option<String>>;
+
+    /// Download and extract a package archive into the given root directory.
+    ///
+    /// The archive download URL is recorded in the returned package directory.
+    /// The archive is verified against the given checksum, if any. With `require_checksum`,
+    /// archives without a known checksum are rejected. Progress is reported as `position`:
+    /// the package's (completed, total) count among the packages being fetched.
+    #[allow(clippy::too_many_arguments)]
+    fn fetch_package(
+        &self,
+        package_name: &str,
+        package_version: &str,
+        checksum: Option<&str>,
+        require_checksum: bool,
+        root_directory: &std::path::Path,
+        position: (usize, usize),
+        session: &crate::session::Session,
+    ) -> Result<PackageDirectory>;
+}
+
+/// Downloaded and extracted package.
+#[derive(Debug, Clone)]
+pub struct PackageDirectory {
+    pub path: std::path::PathBuf,
+
+    /// Crate archive download URL.
+    pub download_url: url::Url,
+
+    /// Crate archive size in bytes.
+    pub download_size: u64,
+
+    /// Total extracted size of crate files in bytes.
+    pub extracted_size: u64,
+}
+
+impl PackageDirectory {
+    /// Record download source and sizes in package details.
+    pub fn update_details(&self, details: &mut crate::resolution::PackageDetails) {
+        details.source = Some(self.download_url.to_string());
+        details.download_size = Some(self.download_size);
+        details.extracted_size = Some(self.extracted_size);
+    }
+}
+
+/// Returns the backend of the registry with the given host name, if supported.
+///
+/// Supported are crates.io and registries configured using OPENFARE_RS_REGISTRIES.
+pub fn backend(host_name: &str) -> Result<Option<Box<dyn Registry>>> {
+    Ok(crates::host_registry(host_name)?
+        .map(|registry| Box::new(crates::CratesRegistry::new(registry)) as Box<dyn Registry>))
+}
+
+/// Returns the backend of the registry serving packages from the given cargo source ID, if
+/// supported.
+pub fn source_backend(source: &str) -> Result<Option<Box<dyn Registry>>> {
+    Ok(crates::source_registry(source)?
+        .map(|registry| Box::new(crates::CratesRegistry::new(registry)) as Box<dyn Registry>))
+}
@@ -0,0 +1,51 @@
+//! Registry index snapshots: registry state at a point in time, given using
+//! `--index-snapshot`.
+use anyhow::Result;
+
+/// Registry index state at a point in time: versions published later are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IndexSnapshot {
+    /// Seconds since the Unix epoch.
+    pub timestamp: i64,
+}
+
+impl IndexSnapshot {
+    /// Returns true if an RFC 3339 timestamp (e.g. a version's publication time) is at or
+    /// before the snapshot time. Unparsable timestamps are not.
+    pub fn includes(&self, timestamp: &str) -> bool {
+        parse_timestamp(timestamp)
+            .map(|timestamp| timestamp <= self.timestamp)
+            .unwrap_or(false)
+    }
+}
+
+impl std::str::FromStr for IndexSnapshot {
+    type Err = anyhow::Error;
+
+    /// Parse an RFC 3339 timestamp (e.g. `2021-06-01T12:00:00Z`) or a `YYYY-MM-DD` date, taken
+    /// as the end of that day.
+    fn from_str(value: &str) -> Result<Self> {
+        parse_timestamp(value)
+            .map(|timestamp| Self { timestamp })
+            .ok_or_else(|| {
+                anyhow::format_err!(
+                    "Invalid index snapshot timestamp (expected RFC 3339 or YYYY-MM-DD): {}",
+                    value
+                )
+            })
+    }
+}
+
+/// Returns seconds since the Unix epoch for an RFC 3339 timestamp or `YYYY-MM-DD` date.
+///
+/// A date is taken as the end of that day (23:59:59 UTC), so that it includes versions
+/// published on that day.
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.timestamp());
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_hms(23, 59, 59).timestamp())
+}
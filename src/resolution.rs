@@ -0,0 +1,265 @@
+/// Additional details for a resolved package.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PackageDetails {
+    /// License expression (`package.license`), otherwise license file path (`package.license-file`).
+    pub license: Option<String>,
+
+    /// Source the package was obtained from: the crate download URL for downloaded
+    /// packages, otherwise the cargo source ID (e.g. `registry+https://...`). `None` for
+    /// local path packages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// Crate archive size in bytes, for downloaded packages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_size: Option<u64>,
+
+    /// Total extracted size of crate files in bytes, for downloaded packages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extracted_size: Option<u64>,
+
+    /// Features activated by the cargo resolver, if resolved (see `cargo metadata`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<Vec<String>>,
+
+    /// Dependency kind, if resolved by cargo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<DependencyKind>,
+
+    /// Direct dependencies, if resolved by cargo. Used to render the dependency tree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Vec<openfare_lib::package::Package>>,
+
+    /// OpenFare lock signature verification outcome, if verification was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<crate::signatures::SignatureStatus>,
+
+    /// IDs of advisories affecting the package, if any (see `--advisories`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advisories: Option<Vec<String>>,
+}
+
+/// Role of a dependency, as given by cargo metadata `dep_kinds`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum DependencyKind {
+    /// Runtime dependency.
+    Normal,
+    /// Dependency of build scripts only.
+    Build,
+    /// Dependency of tests, examples and benchmarks only.
+    Dev,
+}
+
+/// Package details keyed by package.
+pub type PackagesDetails =
+    std::collections::BTreeMap<openfare_lib::package::Package, PackageDetails>;
+
+/// Warning category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningCategory {
+    /// No manifest found, resolution skipped.
+    MissingManifest,
+    /// Dependency resolution incomplete.
+    UnresolvedDependency,
+    /// Package source not supported, OpenFare lock not read.
+    UnsupportedSource,
+    /// Git submodule directories could not be walked.
+    GitSubmodules,
+    /// Security advisory affects a package.
+    Advisory,
+    /// Project OpenFare lock problem.
+    ProjectLock,
+    /// Crate resolved from more than one registry.
+    RegistryChange,
+    /// Package OpenFare lock could not be fetched or parsed (`--keep-going`).
+    LockFailure,
+    /// Package has no OpenFare lock.
+    MissingLock,
+    /// Package skipped, OpenFare lock not read (e.g. path packages of a standalone Cargo.lock).
+    SkippedPackage,
+}
+
+/// Warning raised during resolution.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Warning {
+    pub category: WarningCategory,
+    /// Package the warning relates to, if any.
+    pub package: Option<openfare_lib::package::Package>,
+    pub message: String,
+}
+
+/// OpenFare lock file bytes as published, keyed by package. Only packages with a lock.
+pub type RawLocks = std::collections::BTreeMap<openfare_lib::package::Package, Vec<u8>>;
+
+/// Package lock record streamed as one JSON line per resolved package.
+///
+/// Schema: `{"package": {"registry": ..., "name": ..., "version": ...}, "lock": ...}` where
+/// `lock` is the package OpenFare lock, or `null` if the package has none. Records are
+/// written in completion order.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LockRecord {
+    pub package: openfare_lib::package::Package,
+    pub lock: Option<openfare_lib::lock::Lock>,
+}
+
+/// Resolution run diagnostics.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Diagnostics {
+    /// Bytes downloaded from registries (API responses and crate archives).
+    pub downloaded_bytes: u64,
+    /// Bytes of crate archives and registry responses served from the cache.
+    #[serde(default)]
+    pub cached_bytes: u64,
+    /// Crate archives downloaded into the archive cache.
+    #[serde(default)]
+    pub fetched_archives: u64,
+    /// Time spent downloading, extracting and reading locks, per package.
+    pub timings: std::collections::BTreeMap<openfare_lib::package::Package, std::time::Duration>,
+    /// Warnings raised, in order. Also logged.
+    pub warnings: Vec<Warning>,
+    /// Crate names resolved at more than one version, with versions in ascending order.
+    #[serde(default)]
+    pub duplicate_versions: std::collections::BTreeMap<String, Vec<String>>,
+    /// Crate names resolved from more than one registry, with versions per registry.
+    #[serde(default)]
+    pub registry_changes:
+        std::collections::BTreeMap<String, std::collections::BTreeMap<String, Vec<String>>>,
+    /// Packages of the base Cargo.lock (`--since`) absent from the resolution.
+    #[serde(default)]
+    pub removed_packages: std::collections::BTreeSet<openfare_lib::package::Package>,
+}
+
+impl Diagnostics {
+    /// Returns up to `count` packages with the longest timings, slowest first.
+    pub fn slowest(
+        &self,
+        count: usize,
+    ) -> Vec<(&openfare_lib::package::Package, std::time::Duration)> {
+        let mut timings: Vec<_> = self
+            .timings
+            .iter()
+            .map(|(package, duration)| (package, *duration))
+            .collect();
+        timings.sort_by(|(_, a), (_, b)| b.cmp(a));
+        timings.truncate(count);
+        timings
+    }
+}
+
+/// Outcome of reading a package OpenFare lock.
+///
+/// Unlike `Option<Lock>`, distinguishes packages without a lock from packages whose lock
+/// could not be read. Convert into `Option<Lock>` where the distinction does not matter.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "status", content = "detail", rename_all = "kebab-case")]
+pub enum LockStatus {
+    /// Lock read.
+    Present(openfare_lib::lock::Lock),
+    /// Package has no lock.
+    Absent,
+    /// Package or lock file could not be obtained (e.g. download or I/O failure).
+    FetchFailed(String),
+    /// Lock file could not be parsed.
+    ParseFailed(String),
+    /// Lock not read: the package source is not supported (e.g. a git package listed in a
+    /// standalone Cargo.lock).
+    Unsupported(String),
+}
+
+impl LockStatus {
+    /// Returns the status for an error raised while reading a lock.
+    ///
+    /// I/O errors are fetch failures, other errors (e.g. invalid JSON) are parse failures.
+    pub fn from_read_error(error: &anyhow::Error) -> Self {
+        if error
+            .root_cause()
+            .downcast_ref::<std::io::Error>()
+            .is_some()
+        {
+            Self::FetchFailed(format!("{:#}", error))
+        } else {
+            Self::ParseFailed(format!("{:#}", error))
+        }
+    }
+
+    /// Returns the lock, if read.
+    pub fn lock(&self) -> Option<&openfare_lib::lock::Lock> {
+        match self {
+            Self::Present(lock) => Some(lock),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the lock could not be fetched or parsed.
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::FetchFailed(_) | Self::ParseFailed(_))
+    }
+}
+
+impl From<Option<openfare_lib::lock::Lock>> for LockStatus {
+    fn from(lock: Option<openfare_lib::lock::Lock>) -> Self {
+        match lock {
+            Some(lock) => Self::Present(lock),
+            None => Self::Absent,
+        }
+    }
+}
+
+impl From<LockStatus> for Option<openfare_lib::lock::Lock> {
+    fn from(status: LockStatus) -> Self {
+        match status {
+            LockStatus::Present(lock) => Some(lock),
+            _ => None,
+        }
+    }
+}
+
+/// Lock statuses keyed by package.
+pub type LockStatuses = std::collections::BTreeMap<openfare_lib::package::Package, LockStatus>;
+
+/// Resolution result with additional per package details.
+///
+/// `locks` holds the OpenFare extension result type.
+#[derive(Debug, Clone, Default)]
+pub struct Resolution<T> {
+    pub locks: T,
+    pub packages_details: PackagesDetails,
+    pub raw_locks: RawLocks,
+    /// Lock signature file bytes as published, keyed by package. Only signed locks.
+    pub signatures: RawLocks,
+    /// Lock status of each dependency read. Packages whose lock could not be fetched or
+    /// parsed (see `--keep-going`) are included in `locks` without a lock.
+    pub lock_statuses: LockStatuses,
+    pub diagnostics: Diagnostics,
+}
+
+impl<T> Resolution<T> {
+    /// Map locks, retaining additional details.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Resolution<U> {
+        Resolution {
+            locks: f(self.locks),
+            packages_details: self.packages_details,
+            raw_locks: self.raw_locks,
+            signatures: self.signatures,
+            lock_statuses: self.lock_statuses,
+            diagnostics: self.diagnostics,
+        }
+    }
+
+    /// Returns SHA-256 hashes of OpenFare lock file bytes, keyed by package.
+    ///
+    /// Hashes cover the lock files exactly as published, so they only change when lock
+    /// file contents change. Only packages with a lock are included.
+    pub fn lock_hashes(
+        &self,
+    ) -> std::collections::BTreeMap<openfare_lib::package::Package, String> {
+        self.raw_locks
+            .iter()
+            .map(|(package, bytes)| (package.clone(), crate::locks::lock_hash(bytes)))
+            .collect()
+    }
+}
@@ -0,0 +1,164 @@
+//! Environment self-test.
+//!
+//! Checks registry reachability, crate download and extraction, cache writability and the
+//! embedded cargo library, independently of normal resolution. Run using
+//! `openfare-rs self-test [CRATE]` or `RsExtension::self_test`.
+
+/// Crate queried and downloaded by default: small and without dependencies.
+pub const DEFAULT_CRATE: &str = "cfg-if";
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Check {
+    pub name: String,
+    pub passed: bool,
+    /// Result summary, or the error if the check failed.
+    pub message: String,
+}
+
+/// Self-test outcome: one entry per check, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    /// Returns true if every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for check in &self.checks {
+            writeln!(
+                f,
+                "{} {}: {}",
+                if check.passed { "PASS" } else { "FAIL" },
+                check.name,
+                check.message
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Run all checks using the given crate for the registry and download checks.
+///
+/// Failing checks do not stop later checks, except that the download check is skipped if
+/// the latest crate version could not be found.
+pub fn run(crate_name: &str) -> Report {
+    let session = crate::session::Session::new(&crate::progress::NoProgress);
+    let mut report = Report::default();
+
+    let latest_version = check_latest_version(crate_name, &session);
+    report.checks.push(check("registry", &latest_version));
+    report.checks.push(match &latest_version {
+        Ok(version) => check("download", &check_download(crate_name, version, &session)),
+        Err(_) => Check {
+            name: "download".to_string(),
+            passed: false,
+            message: "Skipped: latest crate version unknown.".to_string(),
+        },
+    });
+    report.checks.push(check("cache", &check_cache()));
+    report.checks.push(check("cargo", &check_cargo_metadata()));
+    report
+}
+
+fn check(name: &str, result: &anyhow::Result<String>) -> Check {
+    Check {
+        name: name.to_string(),
+        passed: result.is_ok(),
+        message: match result {
+            Ok(message) => message.clone(),
+            Err(error) => format!("{:#}", error),
+        },
+    }
+}
+
+fn check_latest_version(
+    crate_name: &str,
+    session: &crate::session::Session,
+) -> anyhow::Result<String> {
+    crate::registries::crates::get_latest_version(crate_name, None, session)?
+        .ok_or_else(|| anyhow::format_err!("No published version found: {}", crate_name))
+}
+
+fn check_download(
+    crate_name: &str,
+    crate_version: &str,
+    session: &crate::session::Session,
+) -> anyhow::Result<String> {
+    let tmp_dir = crate::common::temp_dir()?;
+    let package_directory = crate::registries::crates::setup_package_directory(
+        crate_name,
+        crate_version,
+        &tmp_dir.path().to_path_buf(),
+        &crate::registries::crates::ResolutionOptions::default(),
+        session,
+    )?;
+    Ok(format!(
+        "{} {}: downloaded {} bytes, extracted {} bytes",
+        crate_name,
+        crate_version,
+        package_directory.download_size,
+        package_directory.extracted_size
+    ))
+}
+
+/// Write, read back and remove a probe file in the cache directory.
+fn check_cache() -> anyhow::Result<String> {
+    if !crate::cache::is_enabled() {
+        return Ok("Cache disabled (OPENFARE_RS_NO_CACHE), not checked.".to_string());
+    }
+    let directory = crate::common::cache_directory()?;
+    std::fs::create_dir_all(&directory).map_err(|error| {
+        anyhow::format_err!(
+            "Failed to create cache directory {}: {}",
+            directory.display(),
+            error
+        )
+    })?;
+    let path = directory.join(format!(".self-test.{}", std::process::id()));
+    let contents = b"openfare-rs self-test";
+    let result = std::fs::write(&path, &contents).and_then(|_| std::fs::read(&path));
+    let _ = std::fs::remove_file(&path);
+    match result {
+        Ok(read) if read == contents => Ok(format!("Writable: {}", directory.display())),
+        Ok(_) => Err(anyhow::format_err!(
+            "Cache probe file read back differs: {}",
+            path.display()
+        )),
+        Err(error) => Err(anyhow::format_err!(
+            "Failed to write cache probe file {}: {}",
+            path.display(),
+            error
+        )),
+    }
+}
+
+/// Run cargo metadata on a synthetic manifest without dependencies.
+fn check_cargo_metadata() -> anyhow::Result<String> {
+    let tmp_dir = crate::common::temp_dir()?;
+    let cargo_toml_path = tmp_dir.path().join("Cargo.toml");
+    std::fs::write(
+        &cargo_toml_path,
+        "[package]\nname = \"openfare-self-test\"\nversion = \"0.1.0\"\nedition = \"2018\"\n",
+    )?;
+    std::fs::create_dir_all(tmp_dir.path().join("src"))?;
+    std::fs::write(tmp_dir.path().join("src").join("lib.rs"), "")?;
+
+    let package_names = crate::registries::crates::workspace_package_names(
+        &cargo_toml_path,
+        &crate::registries::crates::ResolutionOptions::default(),
+    )?;
+    if package_names != vec!["openfare-self-test".to_string()] {
+        return Err(anyhow::format_err!(
+            "Unexpected cargo metadata packages: {}",
+            package_names.join(", ")
+        ));
+    }
+    Ok(format!("cargo {}", cargo::version()))
+}
@@ -0,0 +1,279 @@
+/// Crate archive key: registry URL, crate name and version.
+type ArchiveKey = (String, String, String);
+
+/// State shared by registry operations during a single resolution run.
+pub struct Session<'a> {
+    pub progress: &'a dyn crate::progress::Progress,
+    diagnostics: std::sync::Mutex<crate::resolution::Diagnostics>,
+
+    /// Crate archive paths and sizes obtained during this run. Archives are immutable, so
+    /// each is fetched at most once per run, even with the archive cache disabled.
+    archives: std::sync::Mutex<std::collections::BTreeMap<ArchiveKey, (std::path::PathBuf, u64)>>,
+
+    /// Directory holding archives downloaded during this run, created on first use.
+    archives_directory: std::sync::Mutex<Option<crate::interrupt::TempDir>>,
+
+    /// Whether the registry requires authentication, read from its `config.json` once per run.
+    registry_auth_required: std::sync::Mutex<Option<bool>>,
+
+    /// JSON Lines file receiving streamed lock records, created on first use.
+    lock_records: std::sync::Mutex<Option<std::io::BufWriter<std::fs::File>>>,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(progress: &'a dyn crate::progress::Progress) -> Self {
+        Self {
+            progress,
+            diagnostics: std::sync::Mutex::new(crate::resolution::Diagnostics::default()),
+            archives: std::sync::Mutex::new(std::collections::BTreeMap::new()),
+            archives_directory: std::sync::Mutex::new(None),
+            registry_auth_required: std::sync::Mutex::new(None),
+            lock_records: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Returns whether the registry requires authentication, fetched on first use.
+    pub fn registry_auth_required<F>(&self, fetch: F) -> anyhow::Result<bool>
+    where
+        F: FnOnce() -> anyhow::Result<bool>,
+    {
+        let mut auth_required = self
+            .registry_auth_required
+            .lock()
+            .map_err(|_| anyhow::format_err!("Session registry config lock poisoned."))?;
+        if let Some(auth_required) = *auth_required {
+            return Ok(auth_required);
+        }
+        let fetched = fetch()?;
+        *auth_required = Some(fetched);
+        Ok(fetched)
+    }
+
+    /// Returns the path and size of a crate archive obtained earlier in this run, if any.
+    pub fn archive(
+        &self,
+        registry_url: &str,
+        package_name: &str,
+        package_version: &str,
+    ) -> Option<(std::path::PathBuf, u64)> {
+        let key = (
+            registry_url.to_string(),
+            package_name.to_string(),
+            package_version.to_string(),
+        );
+        self.archives
+            .lock()
+            .ok()
+            .and_then(|archives| archives.get(&key).cloned())
+            .filter(|(path, _bytes)| path.is_file())
+    }
+
+    /// Record a crate archive obtained during this run, for reuse by later lookups.
+    pub fn add_archive(
+        &self,
+        registry_url: &str,
+        package_name: &str,
+        package_version: &str,
+        path: &std::path::Path,
+        bytes: u64,
+    ) {
+        if let Ok(mut archives) = self.archives.lock() {
+            archives.insert(
+                (
+                    registry_url.to_string(),
+                    package_name.to_string(),
+                    package_version.to_string(),
+                ),
+                (path.to_path_buf(), bytes),
+            );
+        }
+    }
+
+    /// Returns a path to download a crate archive into, kept until the end of the run.
+    pub fn archive_download_path(
+        &self,
+        registry_url: &str,
+        package_name: &str,
+        package_version: &str,
+    ) -> anyhow::Result<std::path::PathBuf> {
+        let mut archives_directory = self
+            .archives_directory
+            .lock()
+            .map_err(|_| anyhow::format_err!("Session archives directory lock poisoned."))?;
+        if archives_directory.is_none() {
+            *archives_directory = Some(crate::common::temp_dir()?);
+        }
+        let directory = archives_directory
+            .as_ref()
+            .map(|directory| directory.path().to_path_buf())
+            .unwrap_or_default();
+        // Registries may serve crates of the same name: keep their archives apart.
+        let registry_hash = {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            registry_url.hash(&mut hasher);
+            hasher.finish()
+        };
+        Ok(directory.join(format!(
+            "{:x}-{}-{}.crate",
+            registry_hash, package_name, package_version
+        )))
+    }
+
+    /// Write a lock record to the JSON Lines file at the given path.
+    ///
+    /// The file is created (or truncated) on first use, so records of several resolutions
+    /// during this run are kept together. Each record is flushed once written.
+    pub fn write_lock_record(
+        &self,
+        path: &std::path::Path,
+        record: &crate::resolution::LockRecord,
+    ) -> anyhow::Result<()> {
+        use std::io::Write;
+        let mut lock_records = self
+            .lock_records
+            .lock()
+            .map_err(|_| anyhow::format_err!("Session lock records lock poisoned."))?;
+        if lock_records.is_none() {
+            let file = std::fs::File::create(&path).map_err(|error| {
+                anyhow::format_err!(
+                    "Failed to create JSON Lines file {}: {}",
+                    path.display(),
+                    error
+                )
+            })?;
+            *lock_records = Some(std::io::BufWriter::new(file));
+        }
+        if let Some(writer) = lock_records.as_mut() {
+            serde_json::to_writer(&mut *writer, &record)?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Record bytes downloaded from a registry.
+    pub fn add_downloaded_bytes(&self, bytes: u64) {
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            diagnostics.downloaded_bytes += bytes;
+        }
+    }
+
+    /// Record a crate archive downloaded rather than served from the archive cache.
+    pub fn add_fetched_archive(&self) {
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            diagnostics.fetched_archives += 1;
+        }
+    }
+
+    /// Record bytes served from the archive cache instead of downloaded.
+    pub fn add_cached_bytes(&self, bytes: u64) {
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            diagnostics.cached_bytes += bytes;
+        }
+    }
+
+    /// Record time spent on a package. Accumulates across steps.
+    pub fn add_timing(
+        &self,
+        package: &openfare_lib::package::Package,
+        duration: std::time::Duration,
+    ) {
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            *diagnostics.timings.entry(package.clone()).or_default() += duration;
+        }
+    }
+
+    /// Log warning and record it for the caller.
+    pub fn warn(
+        &self,
+        category: crate::resolution::WarningCategory,
+        package: Option<&openfare_lib::package::Package>,
+        message: String,
+    ) {
+        log::warn!("{}", message);
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            diagnostics.warnings.push(crate::resolution::Warning {
+                category,
+                package: package.cloned(),
+                message,
+            });
+        }
+    }
+
+    /// Record crate names resolved at more than one version.
+    pub fn add_duplicate_versions(
+        &self,
+        duplicate_versions: std::collections::BTreeMap<String, Vec<String>>,
+    ) {
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            for (name, versions) in duplicate_versions {
+                let entry = diagnostics.duplicate_versions.entry(name).or_default();
+                entry.extend(versions);
+                crate::locks::sort_versions(entry);
+            }
+        }
+    }
+
+    /// Record crate names resolved from more than one registry, warning once per crate.
+    pub fn add_registry_changes(
+        &self,
+        registry_changes: std::collections::BTreeMap<
+            String,
+            std::collections::BTreeMap<String, Vec<String>>,
+        >,
+    ) {
+        for (name, registries) in registry_changes {
+            let recorded = match self.diagnostics.lock() {
+                Ok(mut diagnostics) => {
+                    let recorded = diagnostics.registry_changes.contains_key(&name);
+                    let entry = diagnostics
+                        .registry_changes
+                        .entry(name.clone())
+                        .or_default();
+                    for (registry, versions) in &registries {
+                        let entry = entry.entry(registry.clone()).or_default();
+                        entry.extend(versions.iter().cloned());
+                        crate::locks::sort_versions(entry);
+                    }
+                    recorded
+                }
+                Err(_) => continue,
+            };
+            if recorded {
+                continue;
+            }
+            let registries: Vec<String> = registries
+                .iter()
+                .map(|(registry, versions)| format!("{}: {}", registry, versions.join(", ")))
+                .collect();
+            self.warn(
+                crate::resolution::WarningCategory::RegistryChange,
+                None,
+                format!(
+                    "Crate {} resolved from several registries ({})",
+                    name,
+                    registries.join("; ")
+                ),
+            );
+        }
+    }
+
+    /// Record packages of the base Cargo.lock absent from the resolution.
+    pub fn add_removed_packages(
+        &self,
+        removed_packages: std::collections::BTreeSet<openfare_lib::package::Package>,
+    ) {
+        if let Ok(mut diagnostics) = self.diagnostics.lock() {
+            diagnostics.removed_packages.extend(removed_packages);
+        }
+    }
+
+    /// Returns diagnostics recorded so far.
+    pub fn diagnostics(&self) -> crate::resolution::Diagnostics {
+        self.diagnostics
+            .lock()
+            .map(|diagnostics| diagnostics.clone())
+            .unwrap_or_default()
+    }
+}
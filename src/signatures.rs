@@ -0,0 +1,69 @@
+//! OpenFare lock signature verification.
+//!
+//! A lock may be accompanied by a detached signature file named after the lock file with a
+//! `.sig` suffix (e.g. `OpenFare.lock.sig`). The signature file holds a hex encoded Ed25519
+//! signature over the lock file bytes as published.
+use anyhow::{format_err, Result};
+
+/// Signature file name suffix.
+pub const SIGNATURE_FILE_SUFFIX: &str = ".sig";
+
+/// Lock signature verification outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureStatus {
+    /// Signature is valid for one of the given public keys.
+    Verified,
+    /// Signature is present but malformed or not valid for any of the given public keys.
+    Unverified,
+    /// Lock has no signature.
+    Unsigned,
+}
+
+/// Parse a hex encoded Ed25519 public key.
+pub fn parse_public_key(value: &str) -> Result<ed25519_dalek::PublicKey> {
+    let bytes = decode_hex(value.trim())
+        .ok_or_else(|| format_err!("Public key is not hex encoded: {}", value))?;
+    ed25519_dalek::PublicKey::from_bytes(&bytes)
+        .map_err(|error| format_err!("Invalid Ed25519 public key {}: {}", value, error))
+}
+
+/// Verify lock file bytes against signature file bytes using the given public keys.
+pub fn verify(
+    lock_bytes: &[u8],
+    signature: Option<&[u8]>,
+    public_keys: &[ed25519_dalek::PublicKey],
+) -> SignatureStatus {
+    use ed25519_dalek::Verifier;
+
+    let signature = match signature {
+        Some(signature) => signature,
+        None => return SignatureStatus::Unsigned,
+    };
+    let signature = match std::str::from_utf8(signature)
+        .ok()
+        .and_then(|signature| decode_hex(signature.trim()))
+        .and_then(|bytes| ed25519_dalek::Signature::try_from(bytes.as_slice()).ok())
+    {
+        Some(signature) => signature,
+        None => return SignatureStatus::Unverified,
+    };
+    if public_keys
+        .iter()
+        .any(|public_key| public_key.verify(lock_bytes, &signature).is_ok())
+    {
+        SignatureStatus::Verified
+    } else {
+        SignatureStatus::Unverified
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 != 0 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,115 @@
+//! Human-readable dependency tree, similar to `cargo tree`.
+
+/// Marker following packages which provide an OpenFare lock.
+pub const LOCK_MARKER: &str = "[💰]";
+
+/// Marker following packages whose dependencies are shown earlier in the tree.
+const DUPLICATE_MARKER: &str = "(*)";
+
+/// Render the dependency tree of resolved package locks.
+///
+/// Dependency edges are taken from package details (see `PackageDetails::dependencies`).
+/// The tree is rooted at the primary package, otherwise at each package which no other
+/// package depends on. As with `cargo tree`, the dependencies of a package are shown once:
+/// later occurrences are marked with `(*)`.
+pub fn render(
+    package_locks: &openfare_lib::package::PackageLocks,
+    packages_details: &crate::resolution::PackagesDetails,
+) -> String {
+    let mut with_locks: std::collections::BTreeSet<_> = package_locks
+        .dependencies_locks
+        .iter()
+        .filter(|(_package, lock)| lock.is_some())
+        .map(|(package, _lock)| package)
+        .collect();
+    if let (Some(primary_package), Some(_lock)) = (
+        &package_locks.primary_package,
+        &package_locks.primary_package_lock,
+    ) {
+        with_locks.insert(primary_package);
+    }
+
+    let roots: Vec<&openfare_lib::package::Package> = match &package_locks.primary_package {
+        Some(primary_package) => vec![primary_package],
+        None => {
+            let dependencies: std::collections::BTreeSet<_> = packages_details
+                .values()
+                .flat_map(|details| details.dependencies.iter().flatten())
+                .collect();
+            package_locks
+                .dependencies_locks
+                .keys()
+                .filter(|package| !dependencies.contains(package))
+                .collect()
+        }
+    };
+
+    let mut tree = Tree {
+        packages_details,
+        with_locks,
+        expanded: std::collections::BTreeSet::new(),
+        output: String::new(),
+    };
+    for root in roots {
+        tree.write(root, "", None);
+    }
+    tree.output
+}
+
+struct Tree<'a> {
+    packages_details: &'a crate::resolution::PackagesDetails,
+    with_locks: std::collections::BTreeSet<&'a openfare_lib::package::Package>,
+    /// Packages whose dependencies were already written.
+    expanded: std::collections::BTreeSet<openfare_lib::package::Package>,
+    output: String,
+}
+
+impl<'a> Tree<'a> {
+    /// Write a package line and its dependencies.
+    ///
+    /// `last` is `None` for roots, otherwise whether the package is its parent's last
+    /// dependency.
+    fn write(
+        &mut self,
+        package: &openfare_lib::package::Package,
+        prefix: &str,
+        last: Option<bool>,
+    ) {
+        let dependencies = self
+            .packages_details
+            .get(package)
+            .and_then(|details| details.dependencies.clone())
+            .unwrap_or_default();
+        let branch = match last {
+            None => "",
+            Some(true) => "└── ",
+            Some(false) => "├── ",
+        };
+        let mut line = format!("{}{}{} v{}", prefix, branch, package.name, package.version);
+        if self.with_locks.contains(package) {
+            line.push(' ');
+            line.push_str(LOCK_MARKER);
+        }
+        let duplicate = !dependencies.is_empty() && self.expanded.contains(package);
+        if duplicate {
+            line.push(' ');
+            line.push_str(DUPLICATE_MARKER);
+        }
+        self.output.push_str(&line);
+        self.output.push('\n');
+        if duplicate {
+            return;
+        }
+        self.expanded.insert(package.clone());
+
+        let child_prefix = match last {
+            None => prefix.to_string(),
+            Some(true) => format!("{}    ", prefix),
+            Some(false) => format!("{}│   ", prefix),
+        };
+        let count = dependencies.len();
+        for (index, dependency) in dependencies.iter().enumerate() {
+            self.write(dependency, &child_prefix, Some(index + 1 == count));
+        }
+    }
+}
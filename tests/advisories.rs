@@ -0,0 +1,63 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+#[test]
+fn test_advisories() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let args = vec![
+        "--advisories".to_string(),
+        common::fixtures_directory()
+            .join("advisories.json")
+            .display()
+            .to_string(),
+    ];
+    let resolution = extension.resolve_project(&project_path, &args)?;
+
+    let (package, details) = resolution
+        .packages_details
+        .iter()
+        .find(|(package, _details)| package.name == "dependency")
+        .expect("dependency details");
+    assert_eq!(
+        details.advisories,
+        Some(vec!["RUSTSEC-0000-0001".to_string()])
+    );
+
+    // The advisory with an invalid requirement is reported, not treated as a match.
+    let warnings: Vec<_> = resolution
+        .diagnostics
+        .warnings
+        .iter()
+        .filter(|warning| {
+            warning.category == openfare_rs_lib::resolution::WarningCategory::Advisory
+                && warning.package.as_ref() == Some(package)
+        })
+        .map(|warning| warning.message.as_str())
+        .collect();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].starts_with("Advisory RUSTSEC-0000-0001 affects dependency 0.1.0"));
+    assert!(warnings[1].starts_with("Advisory RUSTSEC-0000-0003 not checked"));
+    assert!(warnings[1].contains("not a requirement"));
+    Ok(())
+}
+
+#[test]
+fn test_advisory_affects() {
+    let advisory = openfare_rs_lib::advisories::Advisory {
+        id: "RUSTSEC-0000-0001".to_string(),
+        title: None,
+        affected: vec!["<0.2.0".to_string(), "invalid".to_string()],
+    };
+    assert!(advisory.affects("0.1.0").unwrap());
+    assert!(advisory.affects("0.2.0").is_err());
+    assert!(advisory.affects("invalid").is_err());
+
+    let advisory = openfare_rs_lib::advisories::Advisory {
+        affected: vec![],
+        ..advisory
+    };
+    assert!(advisory.affects("0.2.0").unwrap());
+}
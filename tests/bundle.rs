@@ -0,0 +1,104 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+#[test]
+fn test_bundle_round_trip() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let resolution = extension
+        .resolve_project(&project_path, &[])?
+        .map(|locks| locks.package_locks);
+    assert!(!resolution.raw_locks.is_empty());
+
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let bundle_path = tmp_dir.path().join("bundle.json");
+    openfare_rs_lib::bundle::write(&bundle_path, &resolution)?;
+    let bundled = openfare_rs_lib::bundle::read(&bundle_path)?;
+
+    assert_eq!(
+        bundled.locks.primary_package,
+        resolution.locks.primary_package
+    );
+    assert_eq!(
+        serde_json::to_value(bundled.locks.dependencies_locks.iter().collect::<Vec<_>>())?,
+        serde_json::to_value(
+            resolution
+                .locks
+                .dependencies_locks
+                .iter()
+                .collect::<Vec<_>>()
+        )?
+    );
+    assert_eq!(bundled.raw_locks, resolution.raw_locks);
+    Ok(())
+}
+
+#[test]
+fn test_bundle_unsupported_format_version() -> anyhow::Result<()> {
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let bundle_path = tmp_dir.path().join("bundle.json");
+    std::fs::write(
+        &bundle_path,
+        r#"{"format-version": 99, "primary-package": null, "dependencies": []}"#,
+    )?;
+
+    let error = openfare_rs_lib::bundle::read(&bundle_path).expect_err("bundle read");
+    assert!(error
+        .to_string()
+        .contains("Unsupported bundle format version"));
+    Ok(())
+}
+
+/// Recursively copy a directory.
+fn copy_directory(source: &std::path::Path, destination: &std::path::Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(&destination)?;
+    for entry in std::fs::read_dir(&source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = destination.join(entry.file_name());
+        if path.is_dir() {
+            copy_directory(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_write_resolved() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let project_path = tmp_dir.path().join("project");
+    copy_directory(&common::fixtures_directory().join("project"), &project_path)?;
+    assert!(openfare_rs_lib::bundle::read_resolved(&project_path)?.is_none());
+
+    let args = vec!["--write-resolved".to_string()];
+    let result = extension.resolve_project(&project_path, &args)?;
+    let resolved_path = project_path.join(openfare_rs_lib::bundle::RESOLVED_FILE_NAME);
+    let contents = std::fs::read_to_string(&resolved_path)?;
+    let resolved = openfare_rs_lib::bundle::read_resolved(&project_path)?.expect("resolved file");
+
+    let package_locks = result.locks.package_locks;
+    assert_eq!(
+        resolved.locks.primary_package,
+        package_locks.primary_package
+    );
+    assert!(resolved.locks.primary_package_lock.is_some());
+    assert_eq!(
+        serde_json::to_value(&resolved.locks.primary_package_lock)?,
+        serde_json::to_value(&package_locks.primary_package_lock)?
+    );
+    assert_eq!(
+        serde_json::to_value(resolved.locks.dependencies_locks.iter().collect::<Vec<_>>())?,
+        serde_json::to_value(package_locks.dependencies_locks.iter().collect::<Vec<_>>())?
+    );
+
+    // Deterministic: resolving again writes identical contents.
+    extension.resolve_project(&project_path, &args)?;
+    assert_eq!(std::fs::read_to_string(&resolved_path)?, contents);
+    Ok(())
+}
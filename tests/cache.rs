@@ -0,0 +1,88 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+/// Write a cached archive of the given size, last accessed at the given Unix time.
+fn write_archive(
+    path: &std::path::Path,
+    size: usize,
+    accessed: i64,
+) -> anyhow::Result<std::path::PathBuf> {
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(&path, vec![0u8; size])?;
+    filetime::set_file_atime(&path, filetime::FileTime::from_unix_time(accessed, 0))?;
+    Ok(path.to_path_buf())
+}
+
+#[test]
+fn test_prune_evicts_least_recently_accessed() -> anyhow::Result<()> {
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let directory = tmp_dir.path();
+    let newest = write_archive(&directory.join("crates.io/alpha-0.1.0.crate"), 100, 3000)?;
+    let oldest = write_archive(&directory.join("mirror/beta-0.1.0.crate"), 100, 1000)?;
+    let middle = write_archive(&directory.join("crates.io/gamma-0.1.0.crate"), 100, 2000)?;
+    // Only archives are evicted.
+    let other = write_archive(&directory.join("crates.io/notes.txt"), 1000, 0)?;
+
+    let evicted = openfare_rs_lib::cache::prune(directory, 250)?;
+    assert_eq!(evicted, vec![oldest.clone()]);
+    assert!(!oldest.exists());
+
+    let evicted = openfare_rs_lib::cache::prune(directory, 0)?;
+    assert_eq!(evicted, vec![middle, newest]);
+    assert!(other.exists());
+    Ok(())
+}
+
+#[test]
+fn test_prune_within_limit() -> anyhow::Result<()> {
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let directory = tmp_dir.path();
+    let archive = write_archive(&directory.join("crates.io/alpha-0.1.0.crate"), 100, 1000)?;
+
+    assert!(openfare_rs_lib::cache::prune(directory, 100)?.is_empty());
+    assert!(archive.exists());
+    Ok(())
+}
+
+#[test]
+fn test_archive_cache() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    env.set("XDG_CACHE_HOME", tmp_dir.path());
+    env.remove("OPENFARE_RS_NO_CACHE");
+    let extension = openfare_rs_lib::RsExtension::new();
+    let download_count = || {
+        common::requests()
+            .iter()
+            .filter(|request| request.path == "/api/v1/crates/alpha/0.1.0/download")
+            .count()
+    };
+
+    let result = extension.resolve_package("alpha", &Some("0.1.0"), &[])?;
+    assert_eq!(download_count(), 1);
+    assert_eq!(result.diagnostics.cached_bytes, 0);
+    let archives_directory = openfare_rs_lib::cache::archives_directory()?;
+    assert!(archives_directory.starts_with(tmp_dir.path()));
+    assert!(archives_directory.is_dir());
+
+    // Served from the cache.
+    let result = extension.resolve_package("alpha", &Some("0.1.0"), &[])?;
+    assert_eq!(download_count(), 1);
+    assert!(result.diagnostics.cached_bytes > 0);
+    assert!(result.locks.package_locks.primary_package_lock.is_some());
+
+    // Cleared before resolving: downloaded again.
+    let args = vec!["--clear-cache".to_string()];
+    extension.resolve_package("alpha", &Some("0.1.0"), &args)?;
+    assert_eq!(download_count(), 2);
+
+    // Archives exceeding the maximum cache size are evicted once stored.
+    env.set("OPENFARE_RS_CACHE_MAX_SIZE", "0");
+    openfare_rs_lib::cache::clear()?;
+    extension.resolve_package("alpha", &Some("0.1.0"), &[])?;
+    extension.resolve_package("alpha", &Some("0.1.0"), &[])?;
+    assert_eq!(download_count(), 4);
+    Ok(())
+}
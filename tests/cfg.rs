@@ -0,0 +1,42 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+fn resolves_cfg_dependency(args: &[&str]) -> anyhow::Result<bool> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("cfg");
+    let args = args.iter().map(|arg| arg.to_string()).collect();
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    Ok(result
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .any(|package| package.name == "dependency"))
+}
+
+#[test]
+fn test_all_platforms_without_cfg() -> anyhow::Result<()> {
+    assert!(resolves_cfg_dependency(&[])?);
+    Ok(())
+}
+
+#[test]
+fn test_matching_cfg_includes_dependency() -> anyhow::Result<()> {
+    assert!(resolves_cfg_dependency(&["--cfg", "custom"])?);
+    Ok(())
+}
+
+#[test]
+fn test_other_cfg_excludes_dependency() -> anyhow::Result<()> {
+    assert!(!resolves_cfg_dependency(&["--cfg", "unix"])?);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_cfg() -> anyhow::Result<()> {
+    let result = resolves_cfg_dependency(&["--cfg", "key="]);
+    let error = result.expect_err("invalid cfg accepted");
+    assert!(format!("{:#}", error).contains("Invalid cfg 'key='"));
+    Ok(())
+}
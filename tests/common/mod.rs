@@ -0,0 +1,266 @@
+//! Integration test harness.
+//!
+//! Serves a static crates registry fixture (`tests/fixtures/registry`) over a local HTTP
+//! server and points the extension at it using OPENFARE_RS_REGISTRY_URL.
+#![allow(dead_code)]
+
+use std::io::{BufRead, Write};
+
+static SETUP: std::sync::Once = std::sync::Once::new();
+
+/// Request received by the local registry server.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub path: String,
+    /// Header names are lowercase.
+    pub headers: std::collections::BTreeMap<String, String>,
+}
+
+/// Requests received by the local registry server.
+static REQUESTS: once_cell::sync::Lazy<std::sync::Mutex<Vec<Request>>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(Vec::new()));
+
+/// Serialises tests which set process environment variables.
+static ENV_LOCK: once_cell::sync::Lazy<std::sync::Mutex<()>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(()));
+
+fn request_log() -> &'static std::sync::Mutex<Vec<Request>> {
+    &REQUESTS
+}
+
+/// Returns requests received so far.
+pub fn requests() -> Vec<Request> {
+    request_log().lock().unwrap().clone()
+}
+
+pub fn fixtures_directory() -> std::path::PathBuf {
+    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+/// Process environment variables set by a test.
+///
+/// Tests of a binary run in parallel and share the process environment: tests setting
+/// variables are serialised, and the variables are restored once the test completes.
+pub struct Env {
+    saved: Vec<(String, Option<std::ffi::OsString>)>,
+    _guard: std::sync::MutexGuard<'static, ()>,
+}
+
+impl Env {
+    pub fn set<V: AsRef<std::ffi::OsStr>>(&mut self, key: &str, value: V) {
+        self.save(key);
+        std::env::set_var(key, value);
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.save(key);
+        std::env::remove_var(key);
+    }
+
+    fn save(&mut self, key: &str) {
+        if !self
+            .saved
+            .iter()
+            .any(|(saved_key, _value)| saved_key == key)
+        {
+            self.saved.push((key.to_string(), std::env::var_os(key)));
+        }
+    }
+}
+
+impl Drop for Env {
+    fn drop(&mut self) {
+        for (key, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => std::env::set_var(&key, value),
+                None => std::env::remove_var(&key),
+            }
+        }
+    }
+}
+
+/// Take exclusive use of the process environment until the returned `Env` is dropped.
+pub fn env() -> Env {
+    // A failed test poisons the lock, its variables are restored nonetheless.
+    let guard = ENV_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+    Env {
+        saved: vec![],
+        _guard: guard,
+    }
+}
+
+/// Start local registry server once per test binary.
+pub fn setup() {
+    SETUP.call_once(|| {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = handle(stream);
+            }
+        });
+        std::env::set_var("OPENFARE_RS_REGISTRY_URL", &url);
+        // Fixtures must never require network access beyond the local registry.
+        std::env::set_var("CARGO_NET_OFFLINE", "true");
+        // Tests observe registry requests: archives are not cached across tests by default.
+        std::env::set_var("OPENFARE_RS_NO_CACHE", "1");
+    });
+}
+
+/// Start a local registry server using TLS with the given identity. Returns its URL.
+///
+/// Serves the same registry as `setup`, which must be called first.
+pub fn setup_tls(identity: native_tls::Identity) -> anyhow::Result<String> {
+    let acceptor = native_tls::TlsAcceptor::new(identity)?;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("https://{}", listener.local_addr()?);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // Handshakes fail for clients which do not trust the server certificate.
+            if let Ok(stream) = acceptor.accept(stream) {
+                let _ = handle(stream);
+            }
+        }
+    });
+    Ok(url)
+}
+
+fn handle<S: std::io::Read + std::io::Write>(mut stream: S) -> std::io::Result<()> {
+    let mut reader = std::io::BufReader::new(&mut stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut headers = std::collections::BTreeMap::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let if_none_match = headers.get("if-none-match").cloned();
+    request_log().lock().unwrap().push(Request {
+        path: path.to_string(),
+        headers,
+    });
+    let (status, content_type, body) = match route(path) {
+        Some((content_type, body)) => ("200 OK", content_type, body),
+        None => ("404 Not Found", "text/plain", b"Not Found".to_vec()),
+    };
+    // API responses are revalidated using ETags derived from their content.
+    let etag = if content_type == "application/json" {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        body.hash(&mut hasher);
+        Some(format!("\"{:x}\"", hasher.finish()))
+    } else {
+        None
+    };
+    let (status, body) = match (&etag, &if_none_match) {
+        (Some(etag), Some(if_none_match)) if etag == if_none_match => ("304 Not Modified", vec![]),
+        _ => (status, body),
+    };
+    let etag_header = etag
+        .map(|etag| format!("ETag: {}\r\n", etag))
+        .unwrap_or_default();
+    // Simulate a truncated transfer: advertise more bytes than are sent.
+    let content_length = if path.starts_with("/api/v1/crates/truncated/") {
+        body.len() + 16
+    } else {
+        body.len()
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+        status, content_type, content_length, etag_header
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
+
+/// Returns content type and body for a crates.io API or static mirror request path.
+///
+/// Paths prefixed with `/private` are served as a separate registry. Paths prefixed with
+/// `/authenticated` are served as a registry whose `config.json` requires authentication.
+fn route(path: &str) -> Option<(&'static str, Vec<u8>)> {
+    if path == "/authenticated/config.json" {
+        let config = serde_json::json!({"auth-required": true});
+        return Some(("application/json", config.to_string().into_bytes()));
+    }
+    let path = path.strip_prefix("/authenticated").unwrap_or(path);
+    let path = path.strip_prefix("/private").unwrap_or(path);
+    if let Some(file_name) = path.strip_prefix("/mirror/") {
+        let body = std::fs::read(fixtures_directory().join("registry").join(file_name)).ok()?;
+        return Some(("application/octet-stream", body));
+    }
+    let path = path.strip_prefix("/api/v1/crates/")?;
+    let segments: Vec<&str> = path.split('/').collect();
+    match segments.as_slice() {
+        [name] => {
+            let versions = crate_versions(name);
+            let newest_version = versions.last()?;
+            let created_at = created_at_timestamps();
+            let json = serde_json::json!({
+                "crate": {"name": name, "newest_version": newest_version},
+                "versions": versions
+                    .iter()
+                    .map(|version| {
+                        let mut json = serde_json::json!({"num": version});
+                        if let Some(created_at) = created_at.get(&format!("{}-{}", name, version)) {
+                            json["created_at"] = created_at.clone();
+                        }
+                        json
+                    })
+                    .collect::<Vec<_>>(),
+            });
+            Some(("application/json", json.to_string().into_bytes()))
+        }
+        [name, version, "download"] => {
+            let archive_path = fixtures_directory()
+                .join("registry")
+                .join(format!("{}-{}.crate", name, version));
+            let body = std::fs::read(&archive_path).ok()?;
+            Some(("application/octet-stream", body))
+        }
+        _ => None,
+    }
+}
+
+/// Returns fixture version publication timestamps keyed by `<name>-<version>`.
+fn created_at_timestamps() -> std::collections::BTreeMap<String, serde_json::Value> {
+    std::fs::read_to_string(
+        fixtures_directory()
+            .join("registry")
+            .join("created_at.json"),
+    )
+    .ok()
+    .and_then(|contents| serde_json::from_str(&contents).ok())
+    .unwrap_or_default()
+}
+
+/// Returns sorted fixture versions for the given crate name.
+fn crate_versions(name: &str) -> Vec<String> {
+    let prefix = format!("{}-", name);
+    let entries = match std::fs::read_dir(fixtures_directory().join("registry")) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let mut versions: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|file_name| {
+            file_name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".crate"))
+                .map(|version| version.to_string())
+        })
+        .collect();
+    versions.sort();
+    versions
+}
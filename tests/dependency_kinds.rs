@@ -0,0 +1,62 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+#[test]
+fn test_separate_kinds() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("kinds");
+    let args = vec!["--separate-kinds".to_string()];
+    let resolution = extension.resolve_project(&project_path, &args)?;
+
+    let kinds_locks = openfare_rs_lib::locks::separate_kinds(
+        &resolution.locks.package_locks.dependencies_locks,
+        &resolution.packages_details,
+    );
+    let names = |locks: &openfare_rs_lib::locks::DependenciesLocks| -> Vec<String> {
+        locks.keys().map(|package| package.name.clone()).collect()
+    };
+    // The primary package is resolved with its dependencies.
+    // leaf is also a dependency of the build dependency: runtime takes precedence.
+    assert_eq!(
+        names(&kinds_locks.runtime),
+        vec!["kinds", "leaf", "runtime"]
+    );
+    assert_eq!(names(&kinds_locks.build), vec!["build"]);
+    assert_eq!(names(&kinds_locks.dev), vec!["dev"]);
+    assert!(kinds_locks.build.values().all(|lock| lock.is_some()));
+    Ok(())
+}
+
+#[test]
+fn test_separate_kinds_conflicts_with_jsonl() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("kinds");
+    let args = vec![
+        "--separate-kinds".to_string(),
+        "--jsonl".to_string(),
+        "locks.jsonl".to_string(),
+    ];
+    assert!(extension.resolve_project(&project_path, &args).is_err());
+}
+
+#[test]
+fn test_transitive_dev_dependencies_excluded() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("kinds");
+    let resolution = extension.resolve_project(&project_path, &[])?;
+
+    // Root dev-dependencies are included, those of the runtime dependency are not.
+    let names: Vec<String> = resolution
+        .locks
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .map(|package| package.name.clone())
+        .collect();
+    assert_eq!(names, vec!["build", "dev", "kinds", "leaf", "runtime"]);
+    Ok(())
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_dns_failure_retried() {
+    let mut env = common::env();
+    env.set(
+        "OPENFARE_RS_REGISTRY_URL",
+        "http://openfare-rs-missing-host.invalid",
+    );
+    let extension = openfare_rs_lib::RsExtension::new();
+    let start = std::time::Instant::now();
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![]);
+
+    let error = result.expect_err("unresolvable registry host accepted");
+    let message = format!("{:#}", error);
+    assert!(
+        message
+            .contains("Failed to resolve host openfare-rs-missing-host.invalid after 4 attempts"),
+        "{}",
+        message
+    );
+    // Retried after 500, 1000 and 2000 ms.
+    assert!(start.elapsed() >= std::time::Duration::from_millis(3500));
+}
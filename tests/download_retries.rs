@@ -0,0 +1,44 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+/// Download retries used by all tests of this binary.
+const DOWNLOAD_RETRIES: &str = "1";
+
+fn download_count(path: &str) -> usize {
+    common::requests()
+        .iter()
+        .filter(|request| request.path == path)
+        .count()
+}
+
+#[test]
+fn test_download_retries_truncated() {
+    common::setup();
+    let mut env = common::env();
+    env.set("OPENFARE_RS_DOWNLOAD_RETRIES", DOWNLOAD_RETRIES);
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("truncated", &Some("0.1.0"), &vec![]);
+
+    let error = result.expect_err("truncated download extracted");
+    let message = format!("{:#}", error);
+    assert!(message.contains("Truncated download"));
+    assert!(message.contains("after 2 attempts"));
+    assert_eq!(download_count("/api/v1/crates/truncated/0.1.0/download"), 2);
+}
+
+#[test]
+fn test_download_retries_checksum_mismatch() {
+    common::setup();
+    let mut env = common::env();
+    env.set("OPENFARE_RS_DOWNLOAD_RETRIES", DOWNLOAD_RETRIES);
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("checksum_mismatch");
+    let result = extension.project_dependencies_locks(&project_path, &vec![]);
+
+    let error = result.expect_err("archive with mismatched checksum accepted");
+    let message = format!("{:#}", error);
+    assert!(message.contains("Checksum mismatch"));
+    assert!(message.contains("after 2 attempts"));
+    assert_eq!(download_count("/api/v1/crates/alpha/0.1.0/download"), 2);
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_download_template() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    env.set(
+        "OPENFARE_RS_DOWNLOAD_TEMPLATE",
+        "{{{registry_url}}}/mirror/{{name}}-{{version}}.crate",
+    );
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![])?;
+    assert!(result.package_locks.primary_package_lock.is_some());
+
+    let requests = common::requests();
+    assert!(requests
+        .iter()
+        .any(|request| request.path == "/mirror/alpha-0.1.0.crate"));
+    assert!(!requests
+        .iter()
+        .any(|request| request.path.ends_with("/download")));
+    Ok(())
+}
@@ -0,0 +1,17 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_oversized_archive_rejected() {
+    common::setup();
+    let mut env = common::env();
+    env.set("OPENFARE_RS_MAX_EXTRACTED_FILE_SIZE", "4096");
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("large", &Some("0.1.0"), &vec![]);
+
+    let error = result.expect_err("oversized archive extracted");
+    assert!(error
+        .to_string()
+        .contains("exceeds maximum extracted file size"));
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+fn resolves_optional_dependency(args: &[&str]) -> anyhow::Result<bool> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("features");
+    let args = args.iter().map(|arg| arg.to_string()).collect();
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    Ok(result
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .any(|package| package.name == "dependency"))
+}
+
+#[test]
+fn test_inactive_optional_dependency_excluded() -> anyhow::Result<()> {
+    assert!(!resolves_optional_dependency(&[])?);
+    Ok(())
+}
+
+#[test]
+fn test_feature_activates_optional_dependency() -> anyhow::Result<()> {
+    assert!(resolves_optional_dependency(&["--features", "extra"])?);
+    Ok(())
+}
+
+#[test]
+fn test_include_optional() -> anyhow::Result<()> {
+    assert!(resolves_optional_dependency(&["--include-optional"])?);
+    Ok(())
+}
+
+#[test]
+fn test_include_optional_transitive() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("transitive_optional");
+    let names = |args: &[&str]| -> anyhow::Result<Vec<String>> {
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        let result = extension.project_dependencies_locks(&project_path, &args)?;
+        Ok(result
+            .package_locks
+            .dependencies_locks
+            .keys()
+            .filter(|package| Some(*package) != result.package_locks.primary_package.as_ref())
+            .map(|package| package.name.clone())
+            .collect())
+    };
+    // Only optional dependencies of workspace members are included.
+    assert_eq!(names(&["--include-optional"])?, vec!["gated"]);
+    assert_eq!(
+        names(&["--features", "gated/extra"])?,
+        vec!["dependency", "gated"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_activated_features_reported() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("activated_features");
+    let result = extension.resolve_project(&project_path, &[])?;
+
+    let features = result
+        .packages_details
+        .iter()
+        .find(|(package, _details)| package.name == "flagged")
+        .and_then(|(_package, details)| details.features.clone());
+    // Default features disabled by the dependent.
+    assert_eq!(features, Some(vec!["fast".to_string()]));
+    Ok(())
+}
+
+#[test]
+fn test_feature_matrix() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("features");
+    let args: Vec<String> = [
+        "--feature-set",
+        "default",
+        "--feature-set",
+        "extra",
+        "--feature-set",
+        "all",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    let result = extension.resolve_feature_matrix(&project_path, &args)?;
+
+    let resolves_dependency = |feature_set: &str| {
+        result.locks[feature_set]
+            .keys()
+            .any(|package| package.name == "dependency")
+    };
+    assert_eq!(
+        result.locks.keys().collect::<Vec<_>>(),
+        vec!["all", "default", "extra"]
+    );
+    assert!(!resolves_dependency("default"));
+    assert!(resolves_dependency("extra"));
+    assert!(resolves_dependency("all"));
+    Ok(())
+}
+
+#[test]
+fn test_feature_matrix_requires_feature_sets() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("features");
+    let result = extension.resolve_feature_matrix(&project_path, &[]);
+
+    let error = result.expect_err("feature matrix without feature sets accepted");
+    assert!(error.to_string().contains("--feature-set"));
+}
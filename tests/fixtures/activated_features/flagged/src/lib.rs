@@ -0,0 +1,9 @@
+#[cfg(feature = "fast")]
+pub fn mode() -> &'static str {
+    "fast"
+}
+
+#[cfg(not(feature = "fast"))]
+pub fn mode() -> &'static str {
+    "default"
+}
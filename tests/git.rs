@@ -0,0 +1,53 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+fn git(directory: &std::path::Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = std::process::Command::new("git")
+        .args(&["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(directory)
+        .output()?;
+    assert!(output.status.success(), "git {:?} failed", args);
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Create git repository containing a copy of the `project/dependency` fixture.
+fn setup_repository(directory: &std::path::Path) -> anyhow::Result<String> {
+    let source = common::fixtures_directory()
+        .join("project")
+        .join("dependency");
+    std::fs::create_dir_all(directory.join("src"))?;
+    for file in &["Cargo.toml", "OpenFare.lock", "src/lib.rs"] {
+        std::fs::copy(source.join(file), directory.join(file))?;
+    }
+    git(directory, &["init", "--quiet"])?;
+    git(directory, &["add", "."])?;
+    git(directory, &["commit", "--quiet", "-m", "Initial commit"])?;
+    git(directory, &["rev-parse", "HEAD"])
+}
+
+#[test]
+fn test_git_repository() -> anyhow::Result<()> {
+    common::setup();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let rev = setup_repository(tmp_dir.path())?;
+    let git_url = url::Url::from_directory_path(tmp_dir.path())
+        .map_err(|_| anyhow::format_err!("Invalid repository path"))?;
+
+    let extension = openfare_rs_lib::RsExtension::new();
+    let resolution = extension.resolve_git(git_url.as_str(), &rev, &[])?;
+
+    let package_locks = resolution.locks.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "dependency");
+    assert!(package_locks.primary_package_lock.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_invalid_git_revision() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.resolve_git("https://example.com/repo.git", "--upload-pack=x", &[]);
+    assert!(result.is_err());
+}
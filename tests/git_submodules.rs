@@ -0,0 +1,134 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+fn git(directory: &std::path::Path, args: &[&str]) -> anyhow::Result<()> {
+    let output = std::process::Command::new("git")
+        .args(&["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(&["-c", "protocol.file.allow=always"])
+        .args(args)
+        .current_dir(directory)
+        .output()?;
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+/// Create a git repository holding the `project/dependency` fixture crate without its
+/// OpenFare lock, which is in the `openfare` submodule instead. Returns the repository URL.
+fn setup_repository(directory: &std::path::Path) -> anyhow::Result<url::Url> {
+    let source = common::fixtures_directory()
+        .join("project")
+        .join("dependency");
+    let submodule_path = directory.join("submodule");
+    std::fs::create_dir_all(&submodule_path)?;
+    std::fs::copy(
+        source.join("OpenFare.lock"),
+        submodule_path.join("OpenFare.lock"),
+    )?;
+    git(&submodule_path, &["init", "--quiet"])?;
+    git(&submodule_path, &["add", "."])?;
+    git(
+        &submodule_path,
+        &["commit", "--quiet", "-m", "Initial commit"],
+    )?;
+
+    let repository_path = directory.join("repository");
+    std::fs::create_dir_all(repository_path.join("src"))?;
+    for file in &["Cargo.toml", "src/lib.rs"] {
+        std::fs::copy(source.join(file), repository_path.join(file))?;
+    }
+    git(&repository_path, &["init", "--quiet"])?;
+    git(
+        &repository_path,
+        &[
+            "submodule",
+            "--quiet",
+            "add",
+            &submodule_path.display().to_string(),
+            "openfare",
+        ],
+    )?;
+    git(&repository_path, &["add", "."])?;
+    git(
+        &repository_path,
+        &["commit", "--quiet", "-m", "Initial commit"],
+    )?;
+    url::Url::from_directory_path(&repository_path)
+        .map_err(|_| anyhow::format_err!("Invalid repository path"))
+}
+
+#[test]
+fn test_git_submodules() -> anyhow::Result<()> {
+    // Git dependencies are fetched into a private cargo home. The local registry is not
+    // used: cargo must be online to fetch from git.
+    let mut env = common::env();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    env.set("CARGO_HOME", tmp_dir.path().join("cargo_home"));
+    env.set("GIT_CONFIG_COUNT", "1");
+    env.set("GIT_CONFIG_KEY_0", "protocol.file.allow");
+    env.set("GIT_CONFIG_VALUE_0", "always");
+    let repository_url = setup_repository(tmp_dir.path())?;
+
+    let project_path = tmp_dir.path().join("project");
+    std::fs::create_dir_all(project_path.join("src"))?;
+    std::fs::write(
+        project_path.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"project\"\nversion = \"0.1.0\"\nedition = \"2018\"\n\n\
+            [dependencies]\ndependency = {{ git = \"{}\" }}\n",
+            repository_url
+        ),
+    )?;
+    std::fs::write(project_path.join("src").join("lib.rs"), "")?;
+
+    let extension = openfare_rs_lib::RsExtension::new();
+    let dependency_has_lock = |args: &[String]| -> anyhow::Result<bool> {
+        let resolution = extension.resolve_project(&project_path, args)?;
+        let warnings: Vec<_> = resolution
+            .diagnostics
+            .warnings
+            .iter()
+            .filter(|warning| {
+                warning.category == openfare_rs_lib::resolution::WarningCategory::GitSubmodules
+            })
+            .collect();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let (_package, lock) = resolution
+            .locks
+            .package_locks
+            .dependencies_locks
+            .into_iter()
+            .find(|(package, _lock)| package.name == "dependency")
+            .expect("git dependency resolved");
+        Ok(lock.is_some())
+    };
+
+    assert!(!dependency_has_lock(&[])?);
+    // Cargo initializes submodules of its git checkouts.
+    let checkouts_path = tmp_dir
+        .path()
+        .join("cargo_home")
+        .join("git")
+        .join("checkouts");
+    let submodule_lock_found = std::fs::read_dir(&checkouts_path)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| std::fs::read_dir(entry.path()).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| {
+            entry
+                .path()
+                .join("openfare")
+                .join("OpenFare.lock")
+                .is_file()
+        });
+    assert!(submodule_lock_found);
+
+    assert!(dependency_has_lock(&["--git-submodules".to_string()])?);
+    Ok(())
+}
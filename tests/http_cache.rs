@@ -0,0 +1,41 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+#[test]
+fn test_not_modified_response_served_from_cache() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    env.set("XDG_CACHE_HOME", tmp_dir.path());
+    env.remove("OPENFARE_RS_NO_CACHE");
+    let extension = openfare_rs_lib::RsExtension::new();
+    let api_requests = || -> Vec<common::Request> {
+        common::requests()
+            .into_iter()
+            .filter(|request| request.path == "/api/v1/crates/alpha")
+            .collect()
+    };
+
+    // Latest version lookups query the registry API.
+    let result = extension.resolve_package("alpha", &None, &[])?;
+    assert_eq!(
+        result.locks.package_locks.primary_package.unwrap().version,
+        "0.2.0"
+    );
+    let requests = api_requests();
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].headers.contains_key("if-none-match"));
+
+    // Revalidated: the registry responds 304 Not Modified without a body.
+    let result = extension.resolve_package("alpha", &None, &[])?;
+    assert_eq!(
+        result.locks.package_locks.primary_package.unwrap().version,
+        "0.2.0"
+    );
+    let requests = api_requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].headers.contains_key("if-none-match"));
+    assert!(result.diagnostics.cached_bytes > 0);
+    Ok(())
+}
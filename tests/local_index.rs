@@ -0,0 +1,55 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_local_index() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let index_path = common::fixtures_directory().join("index");
+    env.set("OPENFARE_RS_INDEX_PATH", &index_path);
+    let extension = openfare_rs_lib::RsExtension::new();
+    let requested = |path: &str| {
+        common::requests()
+            .iter()
+            .any(|request| request.path == path)
+    };
+
+    // Latest non-yanked version in the local index, without querying the registry.
+    let result = extension.package_dependencies_locks("alpha", &None, &vec![])?;
+    assert_eq!(
+        result.package_locks.primary_package.unwrap().version,
+        "0.1.0"
+    );
+    assert!(!requested("/api/v1/crates/alpha"));
+
+    // Crates absent from the local index are queried from the registry.
+    let result = extension.package_dependencies_locks("beta", &None, &vec![])?;
+    assert_eq!(
+        result.package_locks.primary_package.unwrap().version,
+        "0.1.0"
+    );
+    assert!(requested("/api/v1/crates/beta"));
+
+    // Crates are downloaded from the index config.json download URL.
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let entry_directory = tmp_dir.path().join("al").join("ph");
+    std::fs::create_dir_all(&entry_directory)?;
+    std::fs::copy(
+        index_path.join("al").join("ph").join("alpha"),
+        entry_directory.join("alpha"),
+    )?;
+    let config = serde_json::json!({
+        "dl": format!(
+            "{}/mirror/{{crate}}-{{version}}.crate",
+            std::env::var("OPENFARE_RS_REGISTRY_URL")?
+        ),
+    });
+    std::fs::write(tmp_dir.path().join("config.json"), config.to_string())?;
+    env.set("OPENFARE_RS_INDEX_PATH", tmp_dir.path());
+
+    let result = extension.package_dependencies_locks("alpha", &None, &vec![])?;
+    assert!(result.package_locks.primary_package_lock.is_some());
+    assert!(requested("/mirror/alpha-0.1.0.crate"));
+    Ok(())
+}
@@ -0,0 +1,16 @@
+mod common;
+
+#[test]
+fn test_custom_lock_file_name() -> anyhow::Result<()> {
+    let mut env = common::env();
+    let directory = common::fixtures_directory().join("custom_lock_name");
+    assert!(openfare_rs_lib::get_raw_lock(&directory)?.is_none());
+
+    env.set(
+        "OPENFARE_RS_LOCK_FILENAMES",
+        "missing.json, org.openfare.json",
+    );
+    let raw_lock = openfare_rs_lib::get_raw_lock(&directory)?;
+    assert!(raw_lock.is_some());
+    Ok(())
+}
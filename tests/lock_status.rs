@@ -0,0 +1,134 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+use openfare_rs_lib::resolution::LockStatus;
+
+fn keep_going() -> Vec<String> {
+    vec!["--keep-going".to_string()]
+}
+
+fn status<'a>(
+    lock_statuses: &'a openfare_rs_lib::resolution::LockStatuses,
+    name: &str,
+) -> &'a LockStatus {
+    lock_statuses
+        .iter()
+        .find(|(package, _status)| package.name == name)
+        .map(|(_package, status)| status)
+        .unwrap()
+}
+
+#[test]
+fn test_present_absent_and_parse_failed() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("lock_status");
+    let resolution = extension.resolve_project(&project_path, &keep_going())?;
+
+    let lock_statuses = &resolution.lock_statuses;
+    assert!(matches!(
+        status(lock_statuses, "present"),
+        LockStatus::Present(_)
+    ));
+    assert!(matches!(
+        status(lock_statuses, "absent"),
+        LockStatus::Absent
+    ));
+    match status(lock_statuses, "invalid") {
+        LockStatus::ParseFailed(reason) => assert!(reason.contains("Failed to parse")),
+        status => panic!("unexpected status: {:?}", status),
+    }
+
+    // Failed packages are returned without a lock.
+    let dependencies_locks = &resolution.locks.package_locks.dependencies_locks;
+    for (package, status) in lock_statuses {
+        let lock: Option<openfare_lib::lock::Lock> = status.clone().into();
+        assert_eq!(
+            serde_json::to_value(dependencies_locks.get(package))?,
+            serde_json::to_value(Some(&lock))?
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_failure_without_keep_going() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("lock_status");
+    let result = extension.resolve_project(&project_path, &[]);
+
+    let error = result.expect_err("invalid lock accepted");
+    assert!(error.to_string().contains("Failed to read OpenFare lock"));
+}
+
+#[test]
+fn test_fetch_failed() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("lock_fetch_failed");
+    assert!(extension.resolve_project(&project_path, &[]).is_err());
+
+    let resolution = extension.resolve_project(&project_path, &keep_going())?;
+    assert!(status(&resolution.lock_statuses, "missing").is_failed());
+    assert!(matches!(
+        status(&resolution.lock_statuses, "missing"),
+        LockStatus::FetchFailed(_)
+    ));
+    assert!(matches!(
+        status(&resolution.lock_statuses, "alpha"),
+        LockStatus::Present(_)
+    ));
+    assert!(resolution.diagnostics.warnings.iter().any(
+        |warning| warning.category == openfare_rs_lib::resolution::WarningCategory::LockFailure
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_unsupported_source() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("unsupported_source");
+    // Not a failure: --keep-going is not required.
+    let resolution = extension.resolve_project(&project_path, &[])?;
+
+    let status = status(&resolution.lock_statuses, "unsupported");
+    assert!(!status.is_failed());
+    match status {
+        LockStatus::Unsupported(reason) => assert!(reason.contains("Unsupported package source")),
+        status => panic!("unexpected status: {:?}", status),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_statuses_filtered_with_locks() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("lock_status");
+    let mut args = keep_going();
+    args.push("--filter".to_string());
+    args.push("pres*".to_string());
+    let resolution = extension.resolve_project(&project_path, &args)?;
+
+    // The primary package status is kept.
+    let names: Vec<_> = resolution
+        .lock_statuses
+        .keys()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["lock_status", "present"]);
+
+    let mut args = keep_going();
+    args.push("--with-locks-only".to_string());
+    let resolution = extension.resolve_project(&project_path, &args)?;
+    let names: Vec<_> = resolution
+        .lock_statuses
+        .keys()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["lock_status", "present"]);
+    Ok(())
+}
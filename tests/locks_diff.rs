@@ -0,0 +1,51 @@
+mod common;
+
+fn package(name: &str) -> openfare_lib::package::Package {
+    openfare_lib::package::Package {
+        registry: "crates.io".to_string(),
+        name: name.to_string(),
+        version: "0.1.0".to_string(),
+    }
+}
+
+fn fixture_lock(directory: &str) -> anyhow::Result<openfare_lib::lock::Lock> {
+    let directory = common::fixtures_directory().join(directory);
+    Ok(openfare_rs_lib::get_raw_lock(&directory)?.unwrap().lock)
+}
+
+#[test]
+fn test_locks_diff() -> anyhow::Result<()> {
+    let lock = fixture_lock("project")?;
+    let mut other_lock = lock.clone();
+    other_lock.scheme_version = "2".to_string();
+
+    let old = maplit::btreemap! {
+        package("unchanged") => Some(lock.clone()),
+        package("removed") => Some(lock.clone()),
+        package("changed") => Some(lock.clone()),
+        package("lock_added") => None,
+        package("lock_removed") => Some(lock.clone()),
+    };
+    let new = maplit::btreemap! {
+        package("unchanged") => Some(lock.clone()),
+        package("changed") => Some(other_lock),
+        package("lock_added") => Some(lock),
+        package("lock_removed") => None,
+        package("added") => None,
+    };
+    let locks_diff = openfare_rs_lib::locks::diff(&old, &new);
+
+    assert_eq!(locks_diff.added, maplit::btreeset! {package("added")});
+    assert_eq!(locks_diff.removed, maplit::btreeset! {package("removed")});
+    assert_eq!(
+        locks_diff.changed,
+        maplit::btreeset! {
+            package("changed"),
+            package("lock_added"),
+            package("lock_removed"),
+        }
+    );
+    assert!(!locks_diff.is_empty());
+    assert!(openfare_rs_lib::locks::diff(&new, &new).is_empty());
+    Ok(())
+}
@@ -0,0 +1,24 @@
+#[test]
+fn test_pacer_spaces_requests_across_threads() -> anyhow::Result<()> {
+    let pacer = std::sync::Arc::new(openfare_rs_lib::pacing::Pacer::new());
+    let interval = std::time::Duration::from_millis(100);
+    let start = std::time::Instant::now();
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let pacer = pacer.clone();
+            std::thread::spawn(move || pacer.wait(interval))
+        })
+        .collect();
+    let mut delays = vec![];
+    for thread in threads {
+        delays.push(thread.join().unwrap()?);
+    }
+    delays.sort();
+
+    // The first request is immediate, the last one waits for the fourth slot.
+    assert_eq!(delays[0], std::time::Duration::from_millis(0));
+    assert!(delays[3] > interval * 2);
+    assert!(start.elapsed() >= interval * 3 - std::time::Duration::from_millis(5));
+    Ok(())
+}
@@ -0,0 +1,305 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+fn package(name: &str, version: &str) -> openfare_lib::package::Package {
+    openfare_lib::package::Package {
+        registry: "crates.io".to_string(),
+        name: name.to_string(),
+        version: version.to_string(),
+    }
+}
+
+#[test]
+fn test_package_with_lock() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![])?;
+
+    assert_eq!(result.registry_host_name, "crates.io");
+    let package_locks = result.package_locks;
+    assert_eq!(
+        package_locks.primary_package,
+        Some(package("alpha", "0.1.0"))
+    );
+    assert!(package_locks.primary_package_lock.is_some());
+    assert!(package_locks.dependencies_locks.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_package_without_lock() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("beta", &Some("0.1.0"), &vec![])?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(
+        package_locks.primary_package,
+        Some(package("beta", "0.1.0"))
+    );
+    assert!(package_locks.primary_package_lock.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_latest_version() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("alpha", &None, &vec![])?;
+
+    assert_eq!(
+        result.package_locks.primary_package,
+        Some(package("alpha", "0.2.0"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_mixed_case_package_name() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("Alpha", &None, &vec![])?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(
+        package_locks.primary_package,
+        Some(package("alpha", "0.2.0"))
+    );
+    assert!(package_locks.primary_package_lock.is_some());
+    // Registry queries use the lowercase crate name.
+    let paths: Vec<_> = common::requests()
+        .into_iter()
+        .map(|request| request.path)
+        .collect();
+    assert!(paths.iter().any(|path| path == "/api/v1/crates/alpha"));
+    assert!(paths
+        .iter()
+        .any(|path| path == "/api/v1/crates/alpha/0.2.0/download"));
+    assert!(!paths.iter().any(|path| path.contains("Alpha")));
+    Ok(())
+}
+
+#[test]
+fn test_package_with_gzip_lock() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("gamma", &Some("0.1.0"), &vec![])?;
+
+    assert!(result.package_locks.primary_package_lock.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_zstd_archive() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("delta", &Some("0.1.0"), &vec![])?;
+
+    assert_eq!(
+        result.package_locks.primary_package,
+        Some(package("delta", "0.1.0"))
+    );
+    assert!(result.package_locks.primary_package_lock.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_uncompressed_tar_archive() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("theta", &Some("0.1.0"), &vec![])?;
+
+    assert_eq!(
+        result.package_locks.primary_package,
+        Some(package("theta", "0.1.0"))
+    );
+    assert!(result.package_locks.primary_package_lock.is_some());
+
+    let archive_path = common::fixtures_directory()
+        .join("registry")
+        .join("theta-0.1.0.crate");
+    assert!(openfare_rs_lib::get_raw_lock_from_archive(&archive_path)?.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_package_dev_dependencies() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("epsilon", &Some("0.1.0"), &vec![])?;
+    assert!(result.package_locks.dependencies_locks.is_empty());
+
+    let args = vec!["--include-dev-dependencies".to_string()];
+    let result = extension.package_dependencies_locks("epsilon", &Some("0.1.0"), &args)?;
+    let names: Vec<_> = result
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["helper"]);
+    Ok(())
+}
+
+#[test]
+fn test_package_stream_locks_excludes_primary_package() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let jsonl_path = tmp_dir.path().join("locks.jsonl");
+    let args = vec![
+        "--include-dev-dependencies".to_string(),
+        "--jsonl".to_string(),
+        jsonl_path.display().to_string(),
+    ];
+    let result = extension.package_dependencies_locks("epsilon", &Some("0.1.0"), &args)?;
+    assert_eq!(
+        result.package_locks.primary_package.unwrap().name,
+        "epsilon"
+    );
+    assert!(result.package_locks.dependencies_locks.is_empty());
+
+    let names = std::fs::read_to_string(&jsonl_path)?
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<openfare_rs_lib::resolution::LockRecord>(line)
+                .map(|record| record.package.name)
+        })
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    assert_eq!(names, vec!["helper"]);
+    Ok(())
+}
+
+#[test]
+fn test_non_gzip_download_rejected() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("html", &Some("0.1.0"), &vec![]);
+
+    let error = result.expect_err("non-gzip download extracted");
+    let message = error.to_string();
+    assert!(message.contains("not a gzip or zstd archive"));
+    assert!(message.contains("<!DOCTYPE html>"));
+}
+
+#[test]
+fn test_package_source_url() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let resolution = extension.resolve_package("alpha", &Some("0.1.0"), &[])?;
+
+    let mirror_url = std::env::var("OPENFARE_RS_REGISTRY_URL")?;
+    let details = &resolution.packages_details[&package("alpha", "0.1.0")];
+    assert_eq!(
+        details.source,
+        Some(format!("{}/api/v1/crates/alpha/0.1.0/download", mirror_url))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_package_sizes() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let resolution = extension.resolve_package("alpha", &Some("0.1.0"), &[])?;
+
+    let archive_path = common::fixtures_directory()
+        .join("registry")
+        .join("alpha-0.1.0.crate");
+    let details = &resolution.packages_details[&package("alpha", "0.1.0")];
+    assert_eq!(
+        details.download_size,
+        Some(std::fs::metadata(&archive_path)?.len())
+    );
+    assert!(details.extracted_size.unwrap() > 0);
+    Ok(())
+}
+
+#[test]
+fn test_truncated_download_rejected() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("truncated", &Some("0.1.0"), &vec![]);
+
+    let error = result.expect_err("truncated download extracted");
+    assert!(error.to_string().contains("Truncated download"));
+    let downloads = common::requests()
+        .iter()
+        .filter(|request| request.path == "/api/v1/crates/truncated/0.1.0/download")
+        .count();
+    assert_eq!(downloads, 3);
+}
+
+#[test]
+fn test_mismatched_manifest_rejected() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    // The archive manifest describes package other 0.2.0.
+    let result = extension.package_dependencies_locks("mismatched", &Some("0.1.0"), &vec![]);
+
+    let error = result.expect_err("mismatched crate manifest accepted");
+    let message = format!("{:#}", error);
+    assert!(message.contains("Extracted crate does not match requested package"));
+    assert!(message.contains("Requested: mismatched 0.1.0, found: other 0.2.0"));
+}
+
+#[test]
+fn test_index_snapshot() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let latest_version = |index_snapshot: &str| -> anyhow::Result<String> {
+        let args = vec!["--index-snapshot".to_string(), index_snapshot.to_string()];
+        let result = extension.package_dependencies_locks("alpha", &None, &args)?;
+        Ok(result.package_locks.primary_package.unwrap().version)
+    };
+
+    assert_eq!(latest_version("2021-03-01")?, "0.1.0");
+    // alpha 0.2.0 was published at 2021-06-01T06:00:00Z.
+    assert_eq!(latest_version("2021-06-01T05:59:59Z")?, "0.1.0");
+    assert_eq!(latest_version("2021-06-01T08:00:00+02:00")?, "0.2.0");
+    // A date includes versions published during that day.
+    assert_eq!(latest_version("2021-05-31")?, "0.1.0");
+    assert_eq!(latest_version("2021-06-01")?, "0.2.0");
+    assert_eq!(latest_version("2022-01-01T00:00:00Z")?, "0.2.0");
+
+    let error = latest_version("2020-01-01").expect_err("version found before publication");
+    assert!(error.to_string().contains("Failed to find latest version"));
+    let error = latest_version("June 2021").expect_err("invalid timestamp accepted");
+    assert!(error
+        .to_string()
+        .contains("Invalid index snapshot timestamp"));
+    let error = latest_version("2021-02-29").expect_err("invalid date accepted");
+    assert!(error
+        .to_string()
+        .contains("Invalid index snapshot timestamp"));
+    // 2024 is a leap year.
+    assert_eq!(latest_version("2024-02-29")?, "0.2.0");
+    Ok(())
+}
+
+#[test]
+fn test_nonexistent_version() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.5"), &vec![]);
+
+    let error = result.expect_err("nonexistent version resolved");
+    let message = error.to_string();
+    assert!(message.contains("Version 0.1.5 of alpha does not exist"));
+    assert!(message.contains("0.1.0, 0.2.0"));
+}
+
+#[test]
+fn test_crate_has_lock() -> anyhow::Result<()> {
+    common::setup();
+    assert!(openfare_rs_lib::crate_has_lock("alpha", "0.1.0")?);
+    assert!(!openfare_rs_lib::crate_has_lock("beta", "0.1.0")?);
+    // Compressed lock file.
+    assert!(openfare_rs_lib::crate_lock("gamma", "0.1.0")?.is_some());
+    // The crate dependency tree is not resolved: only the archive is requested.
+    assert!(!common::requests()
+        .iter()
+        .any(|request| request.path == "/api/v1/crates/beta"));
+    Ok(())
+}
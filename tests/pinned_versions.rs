@@ -0,0 +1,25 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_pinned_latest_version() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let pins_path = tmp_dir.path().join("pins.json");
+    std::fs::write(&pins_path, r#"{"alpha": "0.1.0"}"#)?;
+    env.set("OPENFARE_RS_PIN_VERSIONS", &pins_path);
+
+    let extension = openfare_rs_lib::RsExtension::new();
+    let result = extension.package_dependencies_locks("alpha", &None, &vec![])?;
+
+    assert_eq!(
+        result.package_locks.primary_package.unwrap().version,
+        "0.1.0"
+    );
+    assert!(!common::requests()
+        .iter()
+        .any(|request| request.path == "/api/v1/crates/alpha"));
+    Ok(())
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+#[test]
+fn test_prefetch() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    env.set("XDG_CACHE_HOME", tmp_dir.path());
+    env.remove("OPENFARE_RS_NO_CACHE");
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("lockfile");
+
+    let args = vec!["--prefetch".to_string()];
+    let resolution = extension.resolve_project(&project_path, &args)?;
+    assert!(resolution.locks.package_locks.dependencies_locks.is_empty());
+    assert!(resolution.raw_locks.is_empty());
+    assert_eq!(resolution.diagnostics.fetched_archives, 2);
+
+    // Archives are served from the cache.
+    let resolution = extension.resolve_project(&project_path, &[])?;
+    assert_eq!(resolution.diagnostics.fetched_archives, 0);
+    assert!(resolution.diagnostics.cached_bytes > 0);
+    assert_eq!(resolution.locks.package_locks.dependencies_locks.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_prefetch_requires_cargo_lock() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("mixed_registries");
+
+    let result = extension.resolve_project(&project_path, &["--prefetch".to_string()]);
+    let error = result.expect_err("--prefetch accepted for a Cargo.toml project");
+    assert!(error
+        .to_string()
+        .contains("requires a standalone Cargo.lock"));
+    Ok(())
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+/// Records progress updates as (step, package name, completed, total).
+#[derive(Debug, Default)]
+struct RecordingProgress {
+    updates: std::sync::Mutex<Vec<(openfare_rs_lib::progress::Step, String, usize, usize)>>,
+}
+
+impl openfare_rs_lib::progress::Progress for RecordingProgress {
+    fn update(
+        &self,
+        step: openfare_rs_lib::progress::Step,
+        package: &openfare_lib::package::Package,
+        completed: usize,
+        total: usize,
+    ) {
+        self.updates
+            .lock()
+            .unwrap()
+            .push((step, package.name.clone(), completed, total));
+    }
+}
+
+#[test]
+fn test_download_progress_counts() -> anyhow::Result<()> {
+    common::setup();
+    let progress = std::sync::Arc::new(RecordingProgress::default());
+    let extension = openfare_rs_lib::RsExtension::new().with_progress(progress.clone());
+    let project_path = common::fixtures_directory().join("lockfile");
+    extension.resolve_project(&project_path, &[])?;
+
+    // The Cargo.lock lists three packages, of which the path package is not downloaded.
+    let downloads: Vec<_> = progress
+        .updates
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(step, _name, _completed, _total)| {
+            *step == openfare_rs_lib::progress::Step::Download
+        })
+        .map(|(_step, name, completed, total)| (name.clone(), *completed, *total))
+        .collect();
+    assert_eq!(
+        downloads,
+        vec![("alpha".to_string(), 1, 3), ("beta".to_string(), 2, 3)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_since_skips_unchanged_downloads() -> anyhow::Result<()> {
+    common::setup();
+    let progress = std::sync::Arc::new(RecordingProgress::default());
+    let extension = openfare_rs_lib::RsExtension::new().with_progress(progress.clone());
+    let project_path = common::fixtures_directory().join("since");
+    let base_path = project_path.join("base.lock");
+    extension.resolve_project(
+        &project_path,
+        &["--since".to_string(), base_path.display().to_string()],
+    )?;
+
+    // beta 0.1.0 is listed in the base Cargo.lock: it is not downloaded.
+    let downloads: Vec<_> = progress
+        .updates
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(step, _name, _completed, _total)| {
+            *step == openfare_rs_lib::progress::Step::Download
+        })
+        .map(|(_step, name, completed, total)| (name.clone(), *completed, *total))
+        .collect();
+    assert_eq!(
+        downloads,
+        vec![("alpha".to_string(), 1, 3), ("gamma".to_string(), 3, 3)]
+    );
+    Ok(())
+}
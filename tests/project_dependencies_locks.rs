@@ -0,0 +1,1052 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_project_with_path_dependency() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let result = extension.project_dependencies_locks(&project_path, &vec![])?;
+
+    assert_eq!(result.project_path, project_path);
+    let package_locks = result.package_locks;
+    let primary_package = package_locks.primary_package.unwrap();
+    assert_eq!(primary_package.name, "project");
+    assert!(package_locks.primary_package_lock.is_some());
+
+    let dependency = package_locks
+        .dependencies_locks
+        .iter()
+        .find(|(package, _lock)| package.name == "dependency")
+        .expect("path dependency resolved");
+    assert!(dependency.1.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_project_license_details() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let resolution = extension.resolve_project(&project_path, &[])?;
+
+    let primary_package = resolution.locks.package_locks.primary_package.unwrap();
+    let details = &resolution.packages_details[&primary_package];
+    assert_eq!(details.license, Some("MIT".to_string()));
+    Ok(())
+}
+
+#[test]
+fn test_locks_from_directory() -> anyhow::Result<()> {
+    common::setup();
+    let project_path = common::fixtures_directory().join("project");
+    let package_locks = openfare_rs_lib::locks_from_directory(&project_path)?;
+
+    let primary_package = package_locks.primary_package.unwrap();
+    assert_eq!(primary_package.name, "project");
+    assert!(package_locks.primary_package_lock.is_some());
+    assert!(!package_locks
+        .dependencies_locks
+        .contains_key(&primary_package));
+    assert!(package_locks
+        .dependencies_locks
+        .keys()
+        .any(|package| package.name == "dependency"));
+    Ok(())
+}
+
+#[test]
+fn test_project_custom_manifest_path() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let working_directory = common::fixtures_directory();
+    let args = vec![
+        "--manifest-path".to_string(),
+        "generated/Cargo.toml".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&working_directory, &args)?;
+
+    assert_eq!(result.project_path, working_directory.join("generated"));
+    let package_locks = result.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "generated");
+    assert!(package_locks
+        .dependencies_locks
+        .keys()
+        .any(|package| package.name == "dependency"));
+    Ok(())
+}
+
+#[test]
+fn test_project_missing_manifest_path() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec!["--manifest-path".to_string(), "missing.toml".to_string()];
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_coverage_report() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    // Of the Cargo.lock packages, only alpha provides an OpenFare lock.
+    let project_path = common::fixtures_directory().join("lockfile");
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let report_path = tmp_dir.path().join("coverage.json");
+    let args = vec![
+        "--coverage-report".to_string(),
+        report_path.display().to_string(),
+    ];
+    extension.project_dependencies_locks(&project_path, &args)?;
+
+    let coverage: openfare_rs_lib::locks::Coverage =
+        serde_json::from_slice(&std::fs::read(&report_path)?)?;
+    assert_eq!(
+        coverage,
+        openfare_rs_lib::locks::Coverage {
+            total: 2,
+            with_locks: 1,
+            without_locks: 1,
+            uncovered: vec![openfare_rs_lib::locks::PackageVersion {
+                name: "beta".to_string(),
+                version: "0.1.0".to_string(),
+            }],
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_require_all_locks() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let args = vec!["--require-all-locks".to_string()];
+    extension.project_dependencies_locks(&project_path, &args)?;
+
+    let workspace_path = common::fixtures_directory().join("workspace");
+    let result = extension.project_dependencies_locks(&workspace_path, &args);
+    let error = result.expect_err("dependencies without locks");
+    assert!(error.to_string().contains("first 0.1.0"));
+    assert!(!error.to_string().contains("second"));
+
+    let args = vec![
+        "--require-all-locks".to_string(),
+        "--allow".to_string(),
+        "first".to_string(),
+        "--allow".to_string(),
+        "shared".to_string(),
+    ];
+    extension.project_dependencies_locks(&workspace_path, &args)?;
+    Ok(())
+}
+
+#[test]
+fn test_with_locks_only() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("workspace");
+    let result = extension.project_dependencies_locks(&workspace_path, &vec![])?;
+    assert!(result
+        .package_locks
+        .dependencies_locks
+        .values()
+        .any(|lock| lock.is_none()));
+
+    let args = vec!["--with-locks-only".to_string()];
+    let result = extension.project_dependencies_locks(&workspace_path, &args)?;
+    let locks: Vec<_> = result
+        .package_locks
+        .dependencies_locks
+        .iter()
+        .map(|(package, lock)| (package.name.as_str(), lock.is_some()))
+        .collect();
+    assert_eq!(locks, vec![("dependency", true), ("second", true)]);
+    Ok(())
+}
+
+#[test]
+fn test_stream_locks() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let jsonl_path = tmp_dir.path().join("locks.jsonl");
+    let args = vec!["--jsonl".to_string(), jsonl_path.display().to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+
+    // Dependency locks are streamed to the file rather than collected.
+    assert!(result.package_locks.primary_package_lock.is_some());
+    assert!(result.package_locks.dependencies_locks.is_empty());
+    let records = std::fs::read_to_string(&jsonl_path)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<serde_json::Result<Vec<openfare_rs_lib::resolution::LockRecord>>>()?;
+    let dependency = records
+        .iter()
+        .find(|record| record.package.name == "dependency")
+        .expect("dependency record");
+    assert!(dependency.lock.is_some());
+    assert!(!records
+        .iter()
+        .any(|record| record.package.name == "project"));
+    Ok(())
+}
+
+#[test]
+fn test_stream_locks_conflicts_with_filters() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    for flag in [
+        "--require-all-locks",
+        "--with-locks-only",
+        "--verify-signatures",
+        "--merge-registries",
+    ] {
+        let args = vec![
+            "--jsonl".to_string(),
+            "locks.jsonl".to_string(),
+            flag.to_string(),
+        ];
+        assert!(
+            extension
+                .project_dependencies_locks(&project_path, &args)
+                .is_err(),
+            "--jsonl accepted with {}",
+            flag
+        );
+    }
+}
+
+#[test]
+fn test_lock_record_schema() -> anyhow::Result<()> {
+    let record: openfare_rs_lib::resolution::LockRecord = serde_json::from_str(
+        r#"{"package": {"registry": "crates.io", "name": "alpha", "version": "0.1.0"}, "lock": null}"#,
+    )?;
+    assert_eq!(record.package.name, "alpha");
+    assert!(record.lock.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_lock_hashes() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let resolution = extension.resolve_project(&project_path, &[])?;
+
+    let lock_hashes = resolution.lock_hashes();
+    let (_package, hash) = lock_hashes
+        .iter()
+        .find(|(package, _hash)| package.name == "dependency")
+        .expect("dependency lock hash");
+    let lock_bytes = std::fs::read(project_path.join("dependency").join("OpenFare.lock"))?;
+    assert_eq!(hash, &openfare_rs_lib::locks::lock_hash(&lock_bytes));
+    assert_eq!(
+        openfare_rs_lib::locks::lock_hash(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_filter_glob() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let args = vec!["--filter".to_string(), "dep*".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+
+    let names: Vec<_> = result
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["dependency"]);
+    assert_eq!(
+        result.package_locks.primary_package.unwrap().name,
+        "project"
+    );
+
+    let args = vec!["--filter".to_string(), "tokio-*".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    assert!(result.package_locks.dependencies_locks.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_package_glob() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let args = vec!["--package".to_string(), "dep*".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+
+    let names: Vec<_> = result
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["dependency"]);
+
+    let args = vec!["-p".to_string(), "tokio-*".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    assert!(result.package_locks.dependencies_locks.is_empty());
+
+    let args = vec![
+        "--jsonl".to_string(),
+        "locks.jsonl".to_string(),
+        "--package".to_string(),
+        "dep*".to_string(),
+    ];
+    assert!(extension
+        .project_dependencies_locks(&project_path, &args)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_unidentified_dependency_file() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    // Resolving a fixture writes its Cargo.lock, so use a fresh manifest only directory.
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    std::fs::write(
+        tmp_dir.path().join("Cargo.toml"),
+        "[package]\nname = \"manifest-only\"\nversion = \"0.1.0\"\n",
+    )?;
+    let project_path = tmp_dir.path().to_path_buf();
+    let args = vec!["--dependency-file".to_string(), "Cargo.lock".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args);
+
+    let error = result.expect_err("unidentified dependency file selected");
+    assert!(error.to_string().contains("Cargo.lock not identified"));
+    Ok(())
+}
+
+#[test]
+fn test_manifest_without_package() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec![
+        "--manifest-path".to_string(),
+        "manifests/virtual/Cargo.toml".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+
+    let error = result.expect_err("manifest without package accepted");
+    assert!(error.to_string().contains("does not define a package"));
+}
+
+#[test]
+fn test_malformed_package_manifest() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec![
+        "--manifest-path".to_string(),
+        "manifests/malformed/Cargo.toml".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+
+    let error = result.expect_err("malformed manifest accepted");
+    let message = error.to_string();
+    assert!(message.contains("Malformed package manifest"));
+    assert!(message.contains("package.version"));
+}
+
+#[test]
+fn test_strict_manifest_problems() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec![
+        "--manifest-path".to_string(),
+        "manifests/incomplete/Cargo.toml".to_string(),
+        "--strict".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+
+    let error = result.expect_err("incomplete manifest accepted");
+    let message = error.to_string();
+    assert!(message.contains("Invalid manifest"));
+    // All problems are reported together.
+    for problem in [
+        "Field 'package.version' is not a valid semver version",
+        "Missing field 'package.license' or 'package.license-file'.",
+    ] {
+        assert!(message.contains(problem), "{}", message);
+    }
+    assert!(!message.contains("package.name"));
+    assert!(!message.contains("package.description"));
+}
+
+#[test]
+fn test_non_string_package_fields() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    for (manifest_path, expected_message) in [
+        (
+            "manifests/integer_version/Cargo.toml",
+            "expected string for package.version, found integer",
+        ),
+        (
+            "manifests/table_name/Cargo.toml",
+            "expected string for package.name, found table",
+        ),
+    ] {
+        let args = vec!["--manifest-path".to_string(), manifest_path.to_string()];
+        let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+
+        let error = result.expect_err("non-string package field accepted");
+        let message = error.to_string();
+        assert!(message.contains("Malformed package manifest"));
+        assert!(message.contains(expected_message), "{}", message);
+    }
+}
+
+#[test]
+fn test_unstable_cargo_features() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec![
+        "--manifest-path".to_string(),
+        "manifests/nightly/Cargo.toml".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+
+    let error = result.expect_err("unstable cargo features accepted");
+    let message = error.to_string();
+    assert!(message.contains("requires unstable cargo features (edition2024)"));
+    assert!(message.contains("--primary-only"));
+    assert!(message.contains("--manifest-only"));
+
+    // Options passing unstable flags to cargo do not allow nightly-only manifests.
+    for unstable_args in &[
+        vec!["--minimal-versions".to_string()],
+        vec!["--config".to_string(), "net.retry=5".to_string()],
+    ] {
+        let mut args = args.clone();
+        args.extend(unstable_args.iter().cloned());
+        let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+        let error = result.expect_err("unstable cargo features accepted");
+        assert!(error
+            .to_string()
+            .contains("requires unstable cargo features (edition2024)"));
+    }
+
+    let mut args = args;
+    args.push("--primary-only".to_string());
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args)?;
+    assert_eq!(
+        result.package_locks.primary_package.unwrap().name,
+        "nightly"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_workspace_load_error_context() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec![
+        "--manifest-path".to_string(),
+        "manifests/missing_member/Cargo.toml".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+
+    let error = result.expect_err("missing workspace member accepted");
+    let message = error.to_string();
+    assert!(message.contains("Failed to load cargo workspace for manifest"));
+    assert!(message.contains("manifests/missing_member/Cargo.toml"));
+    assert!(message.contains("all workspace members exist"));
+    // The cargo error is preserved as the source.
+    assert!(error.chain().count() > 1);
+}
+
+#[test]
+fn test_resolution_failure_fallback() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    // Depends on a crate which does not exist.
+    let project_path = common::fixtures_directory().join("unresolvable");
+    let resolution = extension.resolve_project(&project_path, &[])?;
+
+    assert_eq!(
+        resolution.locks.package_locks.primary_package.unwrap().name,
+        "unresolvable"
+    );
+    let warnings: Vec<_> = resolution
+        .diagnostics
+        .warnings
+        .iter()
+        .filter(|warning| {
+            warning.category == openfare_rs_lib::resolution::WarningCategory::UnresolvedDependency
+        })
+        .collect();
+    assert!(warnings.iter().any(|warning| warning
+        .message
+        .contains("falling back to workspace packages only")));
+    assert!(warnings.iter().any(|warning| {
+        warning.message.contains("openfare-rs-missing-crate")
+            && warning
+                .package
+                .as_ref()
+                .map(|package| package.name.as_str())
+                == Some("unresolvable")
+    }));
+    Ok(())
+}
+
+#[test]
+fn test_resolution_failure_resolves_dependencies_individually() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    // Depends on a crate which does not exist and on a resolvable path dependency.
+    let project_path = common::fixtures_directory().join("partially_unresolvable");
+    let resolution = extension.resolve_project(&project_path, &[])?;
+
+    let locks: Vec<_> = resolution
+        .locks
+        .package_locks
+        .dependencies_locks
+        .iter()
+        .filter(|(package, _lock)| package.name != "partially_unresolvable")
+        .map(|(package, lock)| (package.name.as_str(), lock.is_some()))
+        .collect();
+    assert_eq!(locks, vec![("dependency", true)]);
+
+    let unresolved: Vec<_> = resolution
+        .diagnostics
+        .warnings
+        .iter()
+        .filter(|warning| {
+            warning.category == openfare_rs_lib::resolution::WarningCategory::UnresolvedDependency
+                && warning.package.is_some()
+        })
+        .map(|warning| warning.message.as_str())
+        .collect();
+    assert_eq!(unresolved.len(), 1);
+    assert!(unresolved[0].contains("openfare-rs-missing-crate 1.0"));
+    Ok(())
+}
+
+#[test]
+fn test_locked_requires_lock_file_update() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    // Fixture without a Cargo.lock: resolution would need to create one.
+    let project_path = common::fixtures_directory().join("locked");
+    let args = vec!["--locked".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args);
+
+    let error = result.expect_err("lock file update allowed with --locked");
+    assert!(error
+        .to_string()
+        .contains("without updating Cargo.lock (--locked)"));
+    assert!(!project_path.join("Cargo.lock").exists());
+}
+
+#[test]
+fn test_validate_project_lock() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("references");
+    let validation = extension.validate_project_lock(&project_path, &[])?;
+
+    assert!(validation.lock_found);
+    assert_eq!(validation.dangling_references, vec!["missing".to_string()]);
+    Ok(())
+}
+
+#[test]
+fn test_validate_project_lock_ignores_filters() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("references");
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let coverage_report_path = tmp_dir.path().join("coverage.json");
+    let args = vec![
+        "--filter".to_string(),
+        "unmatched*".to_string(),
+        "--with-locks-only".to_string(),
+        "--coverage-report".to_string(),
+        coverage_report_path.display().to_string(),
+    ];
+    let validation = extension.validate_project_lock(&project_path, &args)?;
+
+    assert_eq!(validation.dangling_references, vec!["missing".to_string()]);
+    assert!(!coverage_report_path.exists());
+    Ok(())
+}
+
+#[test]
+fn test_validate_project_lock_sources() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+
+    // A lock file starting with a UTF-8 byte order mark.
+    let validation =
+        extension.validate_project_lock(&common::fixtures_directory().join("bom"), &[])?;
+    assert!(validation.lock_found);
+    assert!(validation.dangling_references.is_empty());
+
+    // A lock embedded in the manifest.
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let project_path = tmp_dir.path().join("embedded_references");
+    std::fs::create_dir_all(project_path.join("src"))?;
+    std::fs::write(project_path.join("src").join("lib.rs"), "")?;
+    std::fs::write(
+        project_path.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"embedded_references\"\nversion = \"0.1.0\"\n\
+            edition = \"2018\"\n\n\
+            [dependencies]\ndependency = {{ path = {} }}\n\n\
+            [package.metadata.openfare]\nscheme-version = \"1\"\nplans = {{}}\npayees = {{}}\n\
+            packages = [\"dependency\", \"missing@1.0.0\"]\n",
+            toml::Value::from(
+                common::fixtures_directory()
+                    .join("project")
+                    .join("dependency")
+                    .display()
+                    .to_string()
+            )
+        ),
+    )?;
+    let validation = extension.validate_project_lock(&project_path, &[])?;
+    assert!(validation.lock_found);
+    assert_eq!(
+        validation.dangling_references,
+        vec!["missing@1.0.0".to_string()]
+    );
+
+    // Without a manifest there is no project lock to validate.
+    let empty_path = tmp_dir.path().join("empty");
+    std::fs::create_dir_all(&empty_path)?;
+    let validation = extension.validate_project_lock(&empty_path, &[])?;
+    assert!(!validation.lock_found);
+    Ok(())
+}
+
+#[test]
+fn test_standalone_cargo_lock() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("lockfile");
+    let result = extension.project_dependencies_locks(&project_path, &vec![])?;
+
+    let package_locks = result.package_locks;
+    assert!(package_locks.primary_package.is_none());
+    let locks: Vec<_> = package_locks
+        .dependencies_locks
+        .iter()
+        .map(|(package, lock)| (package.name.as_str(), lock.is_some()))
+        .collect();
+    assert_eq!(locks, vec![("alpha", true), ("beta", false)]);
+    Ok(())
+}
+
+#[test]
+fn test_since_base_cargo_lock() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("since");
+    let base_path = project_path.join("base.lock");
+    let resolution = extension.resolve_project(
+        &project_path,
+        &["--since".to_string(), base_path.display().to_string()],
+    )?;
+
+    let packages: Vec<_> = resolution
+        .locks
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .map(|package| (package.name.as_str(), package.version.as_str()))
+        .collect();
+    assert_eq!(packages, vec![("alpha", "0.2.0"), ("gamma", "0.1.0")]);
+
+    let removed: Vec<_> = resolution
+        .diagnostics
+        .removed_packages
+        .iter()
+        .map(|package| (package.name.as_str(), package.version.as_str()))
+        .collect();
+    assert_eq!(removed, vec![("alpha", "0.1.0"), ("zeta", "0.1.0")]);
+    Ok(())
+}
+
+#[test]
+fn test_packages_without_locks() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("lockfile");
+    let packages = extension.project_packages_without_locks(&project_path, &[])?;
+
+    let packages: Vec<_> = packages
+        .iter()
+        .map(|package| (package.name.as_str(), package.version.as_str()))
+        .collect();
+    assert_eq!(packages, vec![("beta", "0.1.0")]);
+    Ok(())
+}
+
+#[test]
+fn test_require_checksum() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec!["--require-checksum".to_string()];
+
+    // Cargo.lock checksums are verified.
+    let project_path = common::fixtures_directory().join("lockfile");
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    assert_eq!(result.package_locks.dependencies_locks.len(), 2);
+
+    // Neither the Cargo.lock nor the registry provide a checksum.
+    let project_path = common::fixtures_directory().join("no_checksum");
+    let result = extension.project_dependencies_locks(&project_path, &vec![])?;
+    assert_eq!(result.package_locks.dependencies_locks.len(), 1);
+    let error = extension
+        .project_dependencies_locks(&project_path, &args)
+        .expect_err("archive without checksum accepted");
+    assert!(format!("{:#}", error).contains("No checksum available for alpha 0.1.0"));
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_versions() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("duplicates");
+    let resolution = extension.resolve_project(&project_path, &[])?;
+
+    assert_eq!(
+        resolution.diagnostics.duplicate_versions,
+        maplit::btreemap! {
+            "alpha".to_string() => vec!["0.1.0".to_string(), "0.2.0".to_string()],
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_locks_from_metadata() -> anyhow::Result<()> {
+    common::setup();
+    let metadata_json =
+        std::fs::read_to_string(common::fixtures_directory().join("metadata.json"))?;
+    let dependencies_locks = openfare_rs_lib::locks_from_metadata(&metadata_json)?;
+
+    let locks: Vec<_> = dependencies_locks
+        .iter()
+        .map(|(package, lock)| (package.name.as_str(), lock.is_some()))
+        .collect();
+    assert_eq!(locks, vec![("alpha", true), ("beta", false)]);
+    Ok(())
+}
+
+#[test]
+fn test_missing_manifest_warning() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let resolution = extension.resolve_project(&tmp_dir.path().to_path_buf(), &[])?;
+
+    let warnings = resolution.diagnostics.warnings;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].category,
+        openfare_rs_lib::resolution::WarningCategory::MissingManifest
+    );
+    assert!(warnings[0].package.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_byte_order_mark() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("bom");
+    let result = extension.project_dependencies_locks(&project_path, &vec![])?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "bom");
+    assert!(package_locks.primary_package_lock.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_primary_only() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let args = vec!["--primary-only".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "project");
+    assert!(package_locks.primary_package_lock.is_some());
+    assert!(package_locks.dependencies_locks.is_empty());
+
+    let package_locks = openfare_rs_lib::primary_package_locks(&project_path)?;
+    assert_eq!(package_locks.primary_package.unwrap().name, "project");
+    Ok(())
+}
+
+#[test]
+fn test_relative_working_directory() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let relative_path = std::path::PathBuf::from("tests/fixtures/project");
+    let result = extension.project_dependencies_locks(&relative_path, &vec![])?;
+
+    assert_eq!(
+        result.project_path,
+        common::fixtures_directory()
+            .join("project")
+            .canonicalize()?
+    );
+    assert_eq!(
+        result.package_locks.primary_package.unwrap().name,
+        "project"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_missing_working_directory() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let missing_path = common::fixtures_directory().join("missing");
+    let result = extension.project_dependencies_locks(&missing_path, &vec![]);
+
+    let error = result.expect_err("missing working directory accepted");
+    assert!(error.to_string().contains("Directory not found"));
+}
+
+#[test]
+fn test_embedded_project_lock() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("embedded");
+    let result = extension.project_dependencies_locks(&project_path, &vec![])?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "embedded");
+    assert!(package_locks.primary_package_lock.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_manifest_only() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("manifest_only");
+    let args = vec!["--manifest-only".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "manifest-only");
+    let dependencies: Vec<(String, String)> = package_locks
+        .dependencies_locks
+        .keys()
+        .map(|package| (package.name.clone(), package.version.clone()))
+        .collect();
+    // Newest matching version (alpha 0.2.0 does not match), path dependency skipped.
+    assert_eq!(
+        dependencies,
+        vec![
+            ("alpha".to_string(), "0.1.0".to_string()),
+            ("beta".to_string(), "0.1.0".to_string()),
+            ("gamma".to_string(), "0.1.0".to_string()),
+            ("zeta".to_string(), "0.1.0".to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_index_snapshot_requires_manifest_only() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let snapshot_args = vec!["--index-snapshot".to_string(), "2021-03-01".to_string()];
+
+    // Versions resolved by cargo or listed in a Cargo.lock are unaffected by the snapshot.
+    for fixture in &["manifest_only", "lockfile"] {
+        let project_path = common::fixtures_directory().join(fixture);
+        let result = extension.project_dependencies_locks(&project_path, &snapshot_args);
+        let error = result.expect_err("ignored index snapshot accepted");
+        assert!(error.to_string().contains("--index-snapshot only applies"));
+    }
+
+    let project_path = common::fixtures_directory().join("manifest_only");
+    let mut args = snapshot_args;
+    args.push("--manifest-only".to_string());
+    extension.project_dependencies_locks(&project_path, &args)?;
+    Ok(())
+}
+
+#[test]
+fn test_manifest_only_unstable_cargo_features() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec![
+        "--manifest-path".to_string(),
+        "manifests/nightly/Cargo.toml".to_string(),
+        "--manifest-only".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args)?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "nightly");
+    assert!(package_locks.dependencies_locks.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_minimal_versions() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("minimal_versions");
+    // Resolve from vendored dep 0.1.0 and 0.1.1; only 0.1.1 provides a lock.
+    let vendor_path = project_path.join("vendor");
+    let vendor_args = vec![
+        "--config".to_string(),
+        "source.crates-io.replace-with=\"vendored-sources\"".to_string(),
+        "--config".to_string(),
+        format!(
+            "source.vendored-sources.directory={}",
+            toml::Value::from(vendor_path.display().to_string())
+        ),
+    ];
+
+    let dependencies = |args: &Vec<String>| -> anyhow::Result<Vec<(String, String, bool)>> {
+        let result = extension.project_dependencies_locks(&project_path, args)?;
+        Ok(result
+            .package_locks
+            .dependencies_locks
+            .iter()
+            .filter(|(package, _lock)| {
+                Some(*package) != result.package_locks.primary_package.as_ref()
+            })
+            .map(|(package, lock)| {
+                (
+                    package.name.clone(),
+                    package.version.clone(),
+                    lock.is_some(),
+                )
+            })
+            .collect())
+    };
+
+    assert_eq!(
+        dependencies(&vendor_args)?,
+        vec![("dep".to_string(), "0.1.1".to_string(), true)]
+    );
+
+    // Cargo.lock written by the default resolution is ignored.
+    let mut args = vendor_args;
+    args.push("--minimal-versions".to_string());
+    assert_eq!(
+        dependencies(&args)?,
+        vec![("dep".to_string(), "0.1.0".to_string(), false)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_config_overrides() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("minimal_versions");
+
+    let result = extension.project_dependencies_locks(
+        &project_path,
+        &vec!["--config".to_string(), "source.crates-io".to_string()],
+    );
+    let error = format!("{:#}", result.unwrap_err());
+    assert!(
+        error.contains("Invalid cargo configuration override"),
+        "{}",
+        error
+    );
+
+    // Source replacement is only configured using the overrides: the dependency is
+    // resolved from the vendored sources.
+    let vendor_path = project_path.join("vendor");
+    let args = vec![
+        "--config".to_string(),
+        "source.crates-io.replace-with=\"vendored-sources\"".to_string(),
+        "--config".to_string(),
+        format!(
+            "source.vendored-sources.directory={}",
+            toml::Value::from(vendor_path.display().to_string())
+        ),
+    ];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    let dependencies: Vec<_> = result
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .filter(|package| Some(*package) != result.package_locks.primary_package.as_ref())
+        .map(|package| (package.name.clone(), package.version.clone()))
+        .collect();
+    assert_eq!(dependencies, vec![("dep".to_string(), "0.1.1".to_string())]);
+    Ok(())
+}
+
+#[test]
+fn test_fallback_package_name() -> anyhow::Result<()> {
+    common::setup();
+    let manifests_path = common::fixtures_directory().join("manifests");
+
+    // The library target name is preferred.
+    let package_locks =
+        openfare_rs_lib::primary_package_locks(&manifests_path.join("unnamed_lib"))?;
+    let primary_package = package_locks.primary_package.unwrap();
+    assert_eq!(primary_package.name, "named_lib");
+    assert_eq!(primary_package.version, "0.1.0");
+
+    // Otherwise the manifest directory name is used.
+    let package_locks = openfare_rs_lib::primary_package_locks(&manifests_path.join("unnamed"))?;
+    assert_eq!(package_locks.primary_package.unwrap().name, "unnamed");
+    Ok(())
+}
+
+#[test]
+fn test_skipped_package_and_missing_lock_warnings() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("lockfile");
+    let resolution = extension.resolve_project(&project_path, &[])?;
+
+    let warnings: Vec<_> = resolution
+        .diagnostics
+        .warnings
+        .iter()
+        .map(|warning| {
+            (
+                warning.category,
+                warning
+                    .package
+                    .as_ref()
+                    .map(|package| (package.name.as_str(), package.version.as_str())),
+            )
+        })
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                openfare_rs_lib::resolution::WarningCategory::MissingLock,
+                Some(("beta", "0.1.0"))
+            ),
+            (
+                openfare_rs_lib::resolution::WarningCategory::SkippedPackage,
+                Some(("lockfile", "0.1.0"))
+            ),
+        ]
+    );
+    Ok(())
+}
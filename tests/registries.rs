@@ -0,0 +1,193 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+/// Configure the private registry, served by the local registry under `/private`, until
+/// the returned environment is dropped.
+fn setup_private_registry() -> anyhow::Result<common::Env> {
+    common::setup();
+    let mut env = common::env();
+    let registry_url = std::env::var("OPENFARE_RS_REGISTRY_URL")?;
+    let private_url = registry_url.replacen("http://", "http://user:secret@", 1) + "/private";
+    env.set(
+        "OPENFARE_RS_REGISTRIES",
+        format!("registry+https://private.example/index={}", private_url),
+    );
+    Ok(env)
+}
+
+#[test]
+fn test_mixed_registries() -> anyhow::Result<()> {
+    let _env = setup_private_registry()?;
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("registries");
+    let result = extension.project_dependencies_locks(&project_path, &vec![])?;
+
+    let mut locks: Vec<_> = result
+        .package_locks
+        .dependencies_locks
+        .iter()
+        .map(|(package, lock)| {
+            (
+                package.name.as_str(),
+                package.registry.as_str(),
+                lock.is_some(),
+            )
+        })
+        .collect();
+    locks.sort_unstable();
+    assert_eq!(
+        locks,
+        vec![("alpha", "crates.io", true), ("zeta", "127.0.0.1", true)]
+    );
+
+    let requests = common::requests();
+    let private_download = requests
+        .iter()
+        .find(|request| request.path == "/private/api/v1/crates/zeta/0.1.0/download")
+        .expect("private registry download");
+    assert!(private_download.headers.contains_key("authorization"));
+    let public_download = requests
+        .iter()
+        .find(|request| request.path == "/api/v1/crates/alpha/0.1.0/download")
+        .expect("default registry download");
+    assert!(!public_download.headers.contains_key("authorization"));
+    Ok(())
+}
+
+#[test]
+fn test_mixed_registries_cargo_toml() -> anyhow::Result<()> {
+    let _env = setup_private_registry()?;
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("mixed_registries");
+    // Cargo resolves both registries from vendored packages.
+    let vendor_path = toml::Value::from(project_path.join("vendor").display().to_string());
+    let args = vec![
+        "--config".to_string(),
+        "registries.private.index=\"https://private.example/index\"".to_string(),
+        "--config".to_string(),
+        "source.crates-io.replace-with=\"vendored-sources\"".to_string(),
+        "--config".to_string(),
+        "source.private-registry.registry=\"https://private.example/index\"".to_string(),
+        "--config".to_string(),
+        "source.private-registry.replace-with=\"vendored-sources\"".to_string(),
+        "--config".to_string(),
+        format!("source.vendored-sources.directory={}", vendor_path),
+    ];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+
+    let package_locks = result.package_locks;
+    let mut locks: Vec<_> = package_locks
+        .dependencies_locks
+        .iter()
+        .filter(|(package, _lock)| Some(*package) != package_locks.primary_package.as_ref())
+        .map(|(package, lock)| {
+            (
+                package.name.as_str(),
+                package.registry.as_str(),
+                lock.is_some(),
+            )
+        })
+        .collect();
+    locks.sort_unstable();
+    assert_eq!(
+        locks,
+        vec![
+            ("dep", "crates.io", true),
+            ("private_dep", "127.0.0.1", true)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_package_from_registry() -> anyhow::Result<()> {
+    let _env = setup_private_registry()?;
+    let extension = openfare_rs_lib::RsExtension::new();
+    let args = vec!["--registry".to_string(), "127.0.0.1".to_string()];
+    let result = extension.package_dependencies_locks("zeta", &Some("0.1.0"), &args)?;
+
+    assert_eq!(result.registry_host_name, "127.0.0.1");
+    let primary_package = result.package_locks.primary_package.unwrap();
+    assert_eq!(primary_package.registry, "127.0.0.1");
+    assert!(result.package_locks.primary_package_lock.is_some());
+    assert!(common::requests()
+        .iter()
+        .any(|request| request.path == "/private/api/v1/crates/zeta/0.1.0/download"));
+
+    // The registry API is only queried for crates.io.
+    let result = extension.package_dependencies_locks("zeta", &None, &args);
+    let error = result.expect_err("latest version looked up from another registry");
+    assert!(error.to_string().contains("please specify a version"));
+
+    let args = vec!["--registry".to_string(), "unknown.example".to_string()];
+    let result = extension.package_dependencies_locks("zeta", &Some("0.1.0"), &args);
+    let error = result.expect_err("unknown registry accepted");
+    assert!(error
+        .to_string()
+        .contains("Unsupported registry: unknown.example"));
+    Ok(())
+}
+
+fn mirrored_packages(args: &[&str]) -> anyhow::Result<Vec<(String, String)>> {
+    let _env = setup_private_registry()?;
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("mirrored");
+    let args = args.iter().map(|arg| arg.to_string()).collect();
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    Ok(result
+        .package_locks
+        .dependencies_locks
+        .iter()
+        .filter(|(_package, lock)| lock.is_some())
+        .map(|(package, _lock)| (package.name.clone(), package.registry.clone()))
+        .collect())
+}
+
+#[test]
+fn test_registries_kept_distinct_by_default() -> anyhow::Result<()> {
+    let mut packages = mirrored_packages(&[])?;
+    packages.sort();
+    assert_eq!(
+        packages,
+        vec![
+            ("alpha".to_string(), "127.0.0.1".to_string()),
+            ("alpha".to_string(), "crates.io".to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_merge_registries() -> anyhow::Result<()> {
+    assert_eq!(
+        mirrored_packages(&["--merge-registries"])?,
+        vec![("alpha".to_string(), "crates.io".to_string())]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_registry_changes() -> anyhow::Result<()> {
+    let _env = setup_private_registry()?;
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("moved");
+    let result = extension.resolve_project(&project_path, &[])?;
+
+    let diagnostics = result.diagnostics;
+    assert_eq!(
+        diagnostics.registry_changes,
+        maplit::btreemap! {
+            "alpha".to_string() => maplit::btreemap! {
+                "127.0.0.1".to_string() => vec!["0.2.0".to_string()],
+                "crates.io".to_string() => vec!["0.1.0".to_string()],
+            },
+        }
+    );
+    assert!(diagnostics
+        .warnings
+        .iter()
+        .any(|warning| warning.category
+            == openfare_rs_lib::resolution::WarningCategory::RegistryChange));
+    Ok(())
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_auth_required_registry() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let registry_url = std::env::var("OPENFARE_RS_REGISTRY_URL")?;
+    env.set(
+        "OPENFARE_RS_REGISTRY_URL",
+        format!("{}/authenticated", registry_url),
+    );
+    env.set("OPENFARE_RS_REGISTRY_TOKEN", "secret-token");
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("manifest_only");
+    let args = vec!["--manifest-only".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    assert_eq!(result.package_locks.dependencies_locks.len(), 4);
+
+    let requests = common::requests();
+    // Registry configuration is fetched once per run.
+    let config_requests = requests
+        .iter()
+        .filter(|request| request.path == "/authenticated/config.json")
+        .count();
+    assert_eq!(config_requests, 1);
+
+    let entry_requests: Vec<_> = requests
+        .iter()
+        .filter(|request| {
+            request.path.starts_with("/authenticated/api/v1/crates/")
+                && !request.path.ends_with("/download")
+        })
+        .collect();
+    assert_eq!(entry_requests.len(), 4);
+    for request in entry_requests {
+        assert_eq!(
+            request
+                .headers
+                .get("authorization")
+                .map(|value| value.as_str()),
+            Some("secret-token"),
+            "authorization for request: {}",
+            request.path
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_crates_io_token_not_sent_to_mirror() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let registry_url = std::env::var("OPENFARE_RS_REGISTRY_URL")?;
+    let mirror_url = format!("{}/authenticated", registry_url);
+    env.set("OPENFARE_RS_REGISTRY_URL", &mirror_url);
+    env.remove("OPENFARE_RS_REGISTRY_TOKEN");
+    env.set("CARGO_REGISTRY_TOKEN", "crates-io-token");
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let cargo_home = tmp_dir.path().join("cargo_home");
+    std::fs::create_dir_all(&cargo_home)?;
+    std::fs::write(
+        cargo_home.join("credentials.toml"),
+        "[registry]\ntoken = \"crates-io-credential\"\n\n\
+        [registries.internal]\ntoken = \"internal-token\"\n",
+    )?;
+    env.set("CARGO_HOME", &cargo_home);
+    let extension = openfare_rs_lib::RsExtension::new();
+    let previous_requests = common::requests().len();
+
+    // The mirror index URL is not configured as a cargo registry: no token is scoped to it.
+    let error = extension
+        .package_dependencies_locks("alpha", &None, &vec![])
+        .expect_err("crates.io token used for mirror");
+    assert!(
+        error.to_string().contains("no token was found"),
+        "{}",
+        error
+    );
+
+    // The token of the cargo registry with the mirror index URL is used.
+    std::fs::write(
+        cargo_home.join("config.toml"),
+        format!(
+            "[registries.internal]\nindex = \"sparse+{}/\"\n",
+            mirror_url
+        ),
+    )?;
+    extension.package_dependencies_locks("alpha", &None, &vec![])?;
+
+    let authorizations: Vec<_> = common::requests()
+        .into_iter()
+        .skip(previous_requests)
+        .filter(|request| request.path.starts_with("/authenticated/"))
+        .filter_map(|request| request.headers.get("authorization").cloned())
+        .collect();
+    assert!(!authorizations.is_empty());
+    for authorization in authorizations {
+        assert_eq!(authorization, "internal-token");
+    }
+    Ok(())
+}
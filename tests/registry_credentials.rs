@@ -0,0 +1,73 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+/// Records log messages for inspection.
+struct CaptureLogger;
+
+/// Log messages captured so far.
+static MESSAGES: once_cell::sync::Lazy<std::sync::Mutex<Vec<String>>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(Vec::new()));
+
+fn log_messages() -> &'static std::sync::Mutex<Vec<String>> {
+    &MESSAGES
+}
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        log_messages()
+            .lock()
+            .unwrap()
+            .push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CaptureLogger = CaptureLogger;
+
+#[test]
+fn test_basic_auth_redacted() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+    let registry_url = std::env::var("OPENFARE_RS_REGISTRY_URL")?;
+    env.set(
+        "OPENFARE_RS_REGISTRY_URL",
+        registry_url.replacen("http://", "http://user:secret@", 1) + "/private",
+    );
+    let extension = openfare_rs_lib::RsExtension::new();
+    extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![])?;
+
+    let requests = common::requests();
+    let download = requests
+        .iter()
+        .find(|request| request.path == "/private/api/v1/crates/alpha/0.1.0/download")
+        .expect("registry download");
+    // base64("user:secret")
+    assert_eq!(
+        download
+            .headers
+            .get("authorization")
+            .map(|value| value.as_str()),
+        Some("Basic dXNlcjpzZWNyZXQ=")
+    );
+
+    let messages = log_messages().lock().unwrap().clone();
+    assert!(messages
+        .iter()
+        .any(|message| message.contains("/private/api/v1/crates/alpha/0.1.0/download")));
+    for message in messages {
+        assert!(
+            !message.contains("secret"),
+            "credentials logged: {}",
+            message
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_credentials_not_sent_to_lookalike_path() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let registry_url = std::env::var("OPENFARE_RS_REGISTRY_URL")?;
+    env.set(
+        "OPENFARE_RS_REGISTRY_URL",
+        format!("{}/private", registry_url),
+    );
+    // `/priv` is a string prefix of `/private` but not a path segment prefix.
+    env.set(
+        "OPENFARE_RS_REGISTRIES",
+        format!(
+            "registry+https://lookalike.example/index={}/priv",
+            registry_url.replacen("http://", "http://user:secret@", 1)
+        ),
+    );
+    let extension = openfare_rs_lib::RsExtension::new();
+    extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![])?;
+
+    let requests = common::requests();
+    let download = requests
+        .iter()
+        .find(|request| request.path == "/private/api/v1/crates/alpha/0.1.0/download")
+        .expect("registry download");
+    assert!(!download.headers.contains_key("authorization"));
+    Ok(())
+}
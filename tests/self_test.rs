@@ -0,0 +1,35 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+#[test]
+fn test_self_test() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let report = extension.self_test(Some("alpha"));
+
+    let names: Vec<&str> = report
+        .checks
+        .iter()
+        .map(|check| check.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["registry", "download", "cache", "cargo"]);
+    assert!(report.passed(), "{}", report);
+    assert_eq!(report.checks[0].message, "0.2.0");
+    assert!(report.to_string().starts_with("PASS registry: 0.2.0\n"));
+}
+
+#[test]
+fn test_self_test_unknown_crate() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let report = extension.self_test(Some("missing"));
+
+    assert!(!report.passed());
+    assert!(!report.checks[0].passed);
+    // Skipped without a version to download.
+    assert!(!report.checks[1].passed);
+    // Independent checks still run.
+    assert!(report.checks[2].passed);
+    assert!(report.checks[3].passed);
+}
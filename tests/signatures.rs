@@ -0,0 +1,63 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+use openfare_rs_lib::signatures::SignatureStatus;
+
+/// Public key of the key pair which signed the signature fixtures.
+const PUBLIC_KEY: &str = "03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8";
+
+fn signature_statuses(
+    args: &[String],
+) -> anyhow::Result<std::collections::BTreeMap<String, Option<SignatureStatus>>> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("signatures");
+    let resolution = extension.resolve_project(&project_path, args)?;
+    Ok(resolution
+        .packages_details
+        .iter()
+        .filter(|(package, _details)| package.name != "signatures")
+        .map(|(package, details)| (package.name.clone(), details.signature))
+        .collect())
+}
+
+#[test]
+fn test_verify_signatures() -> anyhow::Result<()> {
+    let args = vec![
+        "--verify-signatures".to_string(),
+        "--public-key".to_string(),
+        PUBLIC_KEY.to_string(),
+    ];
+    let statuses = signature_statuses(&args)?;
+    assert_eq!(statuses["signed"], Some(SignatureStatus::Verified));
+    assert_eq!(statuses["tampered"], Some(SignatureStatus::Unverified));
+    assert_eq!(statuses["unsigned"], Some(SignatureStatus::Unsigned));
+    Ok(())
+}
+
+#[test]
+fn test_verify_signatures_unknown_key() -> anyhow::Result<()> {
+    let args = vec![
+        "--verify-signatures".to_string(),
+        "--public-key".to_string(),
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a".to_string(),
+    ];
+    let statuses = signature_statuses(&args)?;
+    assert_eq!(statuses["signed"], Some(SignatureStatus::Unverified));
+    assert_eq!(statuses["unsigned"], Some(SignatureStatus::Unsigned));
+    Ok(())
+}
+
+#[test]
+fn test_signatures_not_verified_by_default() -> anyhow::Result<()> {
+    let statuses = signature_statuses(&[])?;
+    assert!(statuses.values().all(|status| status.is_none()));
+    Ok(())
+}
+
+#[test]
+fn test_verify_signatures_requires_public_key() {
+    let result = signature_statuses(&["--verify-signatures".to_string()]);
+    let error = result.expect_err("verification without public keys");
+    assert!(error.to_string().contains("--public-key"));
+}
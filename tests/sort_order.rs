@@ -0,0 +1,106 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+use openfare_rs_lib::locks::SortOrder;
+
+fn resolve_duplicates() -> anyhow::Result<openfare_rs_lib::locks::DependenciesLocks> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("duplicates");
+    Ok(extension
+        .resolve_project(&project_path, &[])?
+        .locks
+        .package_locks
+        .dependencies_locks)
+}
+
+fn sorted(
+    dependencies_locks: &openfare_rs_lib::locks::DependenciesLocks,
+    sort_order: SortOrder,
+) -> Vec<String> {
+    openfare_rs_lib::locks::sorted_packages(dependencies_locks, sort_order)
+        .into_iter()
+        .map(|package| format!("{} {}", package.name, package.version))
+        .collect()
+}
+
+#[test]
+fn test_sort_by_name() -> anyhow::Result<()> {
+    let dependencies_locks = resolve_duplicates()?;
+    assert_eq!(
+        sorted(&dependencies_locks, SortOrder::Name),
+        vec!["alpha 0.1.0", "alpha 0.2.0", "beta 0.1.0"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sort_by_version() -> anyhow::Result<()> {
+    let dependencies_locks = resolve_duplicates()?;
+    assert_eq!(
+        sorted(&dependencies_locks, SortOrder::Version),
+        vec!["alpha 0.1.0", "beta 0.1.0", "alpha 0.2.0"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sort_by_locked() -> anyhow::Result<()> {
+    let dependencies_locks = resolve_duplicates()?;
+    let locked: Vec<bool> =
+        openfare_rs_lib::locks::sorted_packages(&dependencies_locks, SortOrder::Locked)
+            .into_iter()
+            .map(|package| dependencies_locks[package].is_some())
+            .collect();
+    assert!(locked.contains(&true) && locked.contains(&false));
+    assert!(locked.windows(2).all(|pair| pair[0] >= pair[1]));
+    Ok(())
+}
+
+#[test]
+fn test_unknown_sort_order() {
+    let error = "size".parse::<SortOrder>().expect_err("unknown sort order");
+    assert!(error
+        .to_string()
+        .contains("expected name, version or locked"));
+}
+
+#[test]
+fn test_sorted_bundle() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("duplicates");
+    let resolution = extension
+        .resolve_project(&project_path, &[])?
+        .map(|locks| locks.package_locks);
+
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let bundle_path = tmp_dir.path().join("bundle.json");
+    openfare_rs_lib::bundle::write_sorted(&bundle_path, &resolution, Some(SortOrder::Version))?;
+
+    let bundle: serde_json::Value = serde_json::from_slice(&std::fs::read(&bundle_path)?)?;
+    let names: Vec<_> = bundle["dependencies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|package| {
+            format!(
+                "{} {}",
+                package["name"].as_str().unwrap(),
+                package["version"].as_str().unwrap()
+            )
+        })
+        .collect();
+    assert_eq!(names, vec!["alpha 0.1.0", "beta 0.1.0", "alpha 0.2.0"]);
+    Ok(())
+}
+
+#[test]
+fn test_sort_by_requires_jsonl() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("duplicates");
+    let args = vec!["--sort-by".to_string(), "name".to_string()];
+    let result = extension.resolve_project(&project_path, &args);
+    assert!(result.is_err(), "--sort-by accepted without --jsonl");
+}
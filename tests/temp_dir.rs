@@ -0,0 +1,45 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_temp_dir_fallback() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let missing = tmp_dir.path().join("missing");
+
+    // Explicit temporary directory: no fallback.
+    env.set("OPENFARE_RS_TMPDIR", &missing);
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![]);
+    let error = result.expect_err("missing OPENFARE_RS_TMPDIR accepted");
+    assert!(error.to_string().contains("OPENFARE_RS_TMPDIR"));
+
+    // Unusable system temporary directory: the cache directory is used instead.
+    env.remove("OPENFARE_RS_TMPDIR");
+    env.set("TMPDIR", &missing);
+    env.set("XDG_CACHE_HOME", tmp_dir.path().join("cache"));
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![])?;
+    assert!(result.package_locks.primary_package_lock.is_some());
+    assert!(tmp_dir
+        .path()
+        .join("cache")
+        .join("openfare-rs")
+        .join("tmp")
+        .is_dir());
+    Ok(())
+}
+
+#[test]
+fn test_temp_dir_deregistered_on_drop() -> anyhow::Result<()> {
+    let tmp_dir =
+        openfare_rs_lib::interrupt::TempDir::register(tempdir::TempDir::new("openfare-rs-test")?);
+    let path = tmp_dir.path().to_path_buf();
+    assert!(openfare_rs_lib::interrupt::registered_temp_dirs().contains(&path));
+
+    drop(tmp_dir);
+    assert!(!path.exists());
+    assert!(!openfare_rs_lib::interrupt::registered_temp_dirs().contains(&path));
+    Ok(())
+}
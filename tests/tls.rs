@@ -0,0 +1,53 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_custom_ca_bundle() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let extension = openfare_rs_lib::RsExtension::new();
+
+    env.set(
+        "OPENFARE_RS_CA_BUNDLE",
+        common::fixtures_directory().join("tls").join("missing.pem"),
+    );
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![]);
+    let error = result.expect_err("missing CA bundle accepted");
+    assert!(format!("{:#}", error).contains("Failed to read CA bundle"));
+
+    env.set(
+        "OPENFARE_RS_CA_BUNDLE",
+        common::fixtures_directory().join("tls").join("ca.pem"),
+    );
+    env.set("OPENFARE_RS_MIN_TLS_VERSION", "1.2");
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![])?;
+    assert!(result.package_locks.primary_package_lock.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_tls_registry_with_custom_ca() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    let identity = native_tls::Identity::from_pkcs12(
+        &std::fs::read(common::fixtures_directory().join("tls").join("server.p12"))?,
+        "openfare",
+    )?;
+    let registry_url = common::setup_tls(identity)?;
+    env.set("OPENFARE_RS_REGISTRY_URL", &registry_url);
+    let extension = openfare_rs_lib::RsExtension::new();
+
+    // The server certificate is issued by the test CA, which is not trusted by default.
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![]);
+    assert!(result.is_err(), "untrusted server certificate accepted");
+
+    env.set(
+        "OPENFARE_RS_CA_BUNDLE",
+        common::fixtures_directory().join("tls").join("ca.pem"),
+    );
+    env.set("OPENFARE_RS_MIN_TLS_VERSION", "1.2");
+    let result = extension.package_dependencies_locks("alpha", &Some("0.1.0"), &vec![])?;
+    assert!(result.package_locks.primary_package_lock.is_some());
+    Ok(())
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_toolchain() -> anyhow::Result<()> {
+    common::setup();
+    // Set by the rustup proxy when tests are run using rustup.
+    let toolchain = match std::env::var("RUSTUP_TOOLCHAIN") {
+        Ok(toolchain) => toolchain,
+        Err(_) => return Ok(()),
+    };
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    // Writes Cargo.lock, which is then required to be up to date.
+    extension.project_dependencies_locks(&project_path, &vec![])?;
+
+    let args = vec![
+        "--toolchain".to_string(),
+        toolchain.clone(),
+        "--frozen".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&project_path, &args)?;
+    assert!(result
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .any(|package| package.name == "dependency"));
+    // Process environment is left untouched.
+    assert_eq!(std::env::var("RUSTUP_TOOLCHAIN")?, toolchain);
+    Ok(())
+}
+
+#[test]
+fn test_unknown_toolchain() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("project");
+    let args = vec!["--toolchain".to_string(), "openfare-rs-missing".to_string()];
+    let result = extension.project_dependencies_locks(&project_path, &args);
+
+    let error = result.expect_err("unknown toolchain accepted");
+    assert!(format!("{:#}", error).contains("toolchain openfare-rs-missing"));
+    assert_ne!(
+        std::env::var("RUSTUP_TOOLCHAIN").ok().as_deref(),
+        Some("openfare-rs-missing")
+    );
+}
+
+#[test]
+fn test_rustc_rejects_unstable_cargo_features() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    // rustc is applied as a cargo configuration override, which requires unstable options.
+    let args = vec![
+        "--manifest-path".to_string(),
+        "manifests/nightly/Cargo.toml".to_string(),
+        "--rustc".to_string(),
+        "rustc".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&common::fixtures_directory(), &args);
+
+    let error = result.expect_err("unstable cargo features accepted");
+    assert!(error
+        .to_string()
+        .contains("requires unstable cargo features (edition2024)"));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+#[test]
+fn test_dependency_tree() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("tree");
+    let args = vec!["--tree".to_string()];
+    let resolution = extension.resolve_project(&project_path, &args)?;
+
+    let tree = openfare_rs_lib::tree::render(
+        &resolution.locks.package_locks,
+        &resolution.packages_details,
+    );
+    // The dependencies of shared are shown once.
+    let expected = [
+        "tree v0.1.0",
+        "├── left v0.1.0",
+        "│   └── shared v0.1.0 [💰]",
+        "│       └── leaf v0.1.0",
+        "└── right v0.1.0",
+        "    └── shared v0.1.0 [💰] (*)",
+    ];
+    assert_eq!(tree.lines().collect::<Vec<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn test_dependency_tree_conflicts_with_jsonl() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let project_path = common::fixtures_directory().join("tree");
+    let args = vec![
+        "--tree".to_string(),
+        "--jsonl".to_string(),
+        "locks.jsonl".to_string(),
+    ];
+    assert!(extension.resolve_project(&project_path, &args).is_err());
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_user_agent_override() -> anyhow::Result<()> {
+    common::setup();
+    let mut env = common::env();
+    env.set(
+        "OPENFARE_RS_USER_AGENT",
+        "example-agent/1.0 (ops@example.com)",
+    );
+    let extension = openfare_rs_lib::RsExtension::new();
+    extension.package_dependencies_locks("alpha", &None, &vec![])?;
+
+    let requests = common::requests();
+    assert!(requests
+        .iter()
+        .any(|request| request.path.ends_with("/download")));
+    for request in requests {
+        assert_eq!(
+            request
+                .headers
+                .get("user-agent")
+                .map(|value| value.as_str()),
+            Some("example-agent/1.0 (ops@example.com)"),
+            "user agent for request: {}",
+            request.path
+        );
+    }
+    Ok(())
+}
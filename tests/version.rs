@@ -0,0 +1,16 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_version_offline() {
+    common::setup();
+    let mut env = common::env();
+    env.set("CARGO_NET_OFFLINE", "true");
+    let extension = openfare_rs_lib::RsExtension::new();
+    let version = extension.version();
+
+    assert!(version.contains(&format!("CARGO_PKG_VERSION: {}", env!("CARGO_PKG_VERSION"))));
+    assert!(version.contains("; cargo: "));
+    assert!(version.contains("; offline: true"), "{}", version);
+}
@@ -0,0 +1,246 @@
+mod common;
+
+use openfare_lib::extension::{Extension, FromLib};
+
+#[test]
+fn test_select_workspace_member() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("workspace");
+    let args = vec!["--package".to_string(), "second".to_string()];
+    let result = extension.project_dependencies_locks(&workspace_path, &args)?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "second");
+    assert!(package_locks.primary_package_lock.is_some());
+    assert!(package_locks.dependencies_locks.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_select_workspace_member_with_filter() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("workspace");
+    let args = vec![
+        "--package".to_string(),
+        "first".to_string(),
+        "--filter".to_string(),
+        "dep*".to_string(),
+    ];
+    let result = extension.project_dependencies_locks(&workspace_path, &args)?;
+
+    let package_locks = result.package_locks;
+    assert_eq!(package_locks.primary_package.unwrap().name, "first");
+    let names: Vec<_> = package_locks
+        .dependencies_locks
+        .keys()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["dependency"]);
+    Ok(())
+}
+
+#[test]
+fn test_select_workspace_member_stream_locks() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("workspace");
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let jsonl_path = tmp_dir.path().join("locks.jsonl");
+    let args = vec![
+        "--package".to_string(),
+        "first".to_string(),
+        "--jsonl".to_string(),
+        jsonl_path.display().to_string(),
+    ];
+
+    // Records are streamed as read, or buffered when sorted.
+    for sort_args in [vec![], vec!["--sort-by".to_string(), "name".to_string()]] {
+        let args: Vec<_> = args.iter().cloned().chain(sort_args).collect();
+        let result = extension.project_dependencies_locks(&workspace_path, &args)?;
+
+        let package_locks = result.package_locks;
+        assert_eq!(package_locks.primary_package.unwrap().name, "first");
+        assert!(package_locks.dependencies_locks.is_empty());
+        let names: Vec<_> = std::fs::read_to_string(&jsonl_path)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<Vec<openfare_rs_lib::resolution::LockRecord>>>()?
+            .into_iter()
+            .map(|record| record.package.name)
+            .collect();
+        assert_eq!(names, vec!["dependency".to_string()]);
+        std::fs::remove_file(&jsonl_path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_all_workspace_members() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("workspace");
+    let result = extension.project_dependencies_locks(&workspace_path, &vec![])?;
+
+    let names: Vec<_> = result
+        .package_locks
+        .dependencies_locks
+        .keys()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert!(names.contains(&"first"));
+    assert!(names.contains(&"second"));
+    assert!(names.contains(&"dependency"));
+    Ok(())
+}
+
+#[test]
+fn test_unknown_workspace_member() {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("workspace");
+    let args = vec!["--package".to_string(), "third".to_string()];
+    let result = extension.project_dependencies_locks(&workspace_path, &args);
+
+    let error = result.expect_err("unknown workspace member selected");
+    assert!(error.to_string().contains("not found in workspace"));
+}
+
+#[test]
+fn test_resolve_workspace_members() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("workspace");
+    let args = vec!["--jobs".to_string(), "2".to_string()];
+    let result = extension.resolve_workspace_members(&workspace_path, &args)?;
+
+    let members: Vec<_> = result
+        .locks
+        .keys()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(members, vec!["first", "second", "shared"]);
+
+    let dependencies = |name: &str| {
+        result
+            .locks
+            .iter()
+            .find(|(package, _locks)| package.name == name)
+            .map(|(_package, locks)| locks.dependencies_locks.clone())
+            .unwrap()
+    };
+    let first = dependencies("first");
+    let shared = dependencies("shared");
+    assert_eq!(
+        first
+            .keys()
+            .map(|package| package.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["dependency"]
+    );
+    assert_eq!(
+        serde_json::to_value(first.iter().collect::<Vec<_>>())?,
+        serde_json::to_value(shared.iter().collect::<Vec<_>>())?
+    );
+    assert!(dependencies("second").is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_default_members_only() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("default_members");
+    let names = |args: &Vec<String>| -> anyhow::Result<Vec<String>> {
+        let result = extension.project_dependencies_locks(&workspace_path, args)?;
+        Ok(result
+            .package_locks
+            .dependencies_locks
+            .keys()
+            .map(|package| package.name.clone())
+            .collect())
+    };
+
+    assert_eq!(names(&vec![])?, vec!["dependency", "excluded", "included"]);
+
+    // Non-default member `excluded` and its dependencies are not resolved.
+    let args = vec!["--default-members-only".to_string()];
+    assert_eq!(names(&args)?, vec!["included"]);
+    Ok(())
+}
+
+#[test]
+fn test_members_since_coverage_report() -> anyhow::Result<()> {
+    common::setup();
+    let extension = openfare_rs_lib::RsExtension::new();
+    let workspace_path = common::fixtures_directory().join("shared_dependency_workspace");
+    let tmp_dir = tempdir::TempDir::new("openfare-rs-test")?;
+    let base_path = tmp_dir.path().join("base.lock");
+    std::fs::write(
+        &base_path,
+        "version = 3\n\n\
+        [[package]]\nname = \"alpha\"\nversion = \"0.0.9\"\n\
+        source = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n\
+        [[package]]\nname = \"first\"\nversion = \"0.1.0\"\n\n\
+        [[package]]\nname = \"gamma\"\nversion = \"0.1.0\"\n\
+        source = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n\
+        [[package]]\nname = \"second\"\nversion = \"0.1.0\"\n",
+    )?;
+    let coverage_report_path = tmp_dir.path().join("coverage.json");
+    let args = vec![
+        "--config".to_string(),
+        "source.crates-io.replace-with=\"vendored-sources\"".to_string(),
+        "--config".to_string(),
+        format!(
+            "source.vendored-sources.directory={}",
+            toml::Value::from(workspace_path.join("vendor").display().to_string())
+        ),
+        "--since".to_string(),
+        base_path.display().to_string(),
+        "--coverage-report".to_string(),
+        coverage_report_path.display().to_string(),
+    ];
+    let result = extension.resolve_workspace_members(&workspace_path, &args)?;
+
+    let members: Vec<(&str, Vec<&str>)> = result
+        .locks
+        .iter()
+        .map(|(member, package_locks)| {
+            let names = package_locks
+                .dependencies_locks
+                .keys()
+                .map(|package| package.name.as_str())
+                .collect();
+            (member.name.as_str(), names)
+        })
+        .collect();
+    assert_eq!(
+        members,
+        vec![("first", vec!["alpha", "beta"]), ("second", vec!["alpha"])]
+    );
+
+    // Neither members nor packages resolved by another member are removed.
+    let removed: Vec<_> = result
+        .diagnostics
+        .removed_packages
+        .iter()
+        .map(|package| (package.name.as_str(), package.version.as_str()))
+        .collect();
+    assert_eq!(removed, vec![("alpha", "0.0.9"), ("gamma", "0.1.0")]);
+
+    // Coverage covers the dependencies of every member.
+    let coverage: openfare_rs_lib::locks::Coverage =
+        serde_json::from_str(&std::fs::read_to_string(&coverage_report_path)?)?;
+    assert_eq!(coverage.total, 2);
+    assert_eq!(coverage.with_locks, 1);
+    assert_eq!(
+        coverage
+            .uncovered
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["beta"]
+    );
+    Ok(())
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use openfare_lib::extension::FromLib;
+
+/// Records the names of packages whose locks are read.
+#[derive(Debug, Default)]
+struct RecordingProgress {
+    read_locks: std::sync::Mutex<Vec<String>>,
+}
+
+impl openfare_rs_lib::progress::Progress for RecordingProgress {
+    fn update(
+        &self,
+        step: openfare_rs_lib::progress::Step,
+        package: &openfare_lib::package::Package,
+        _completed: usize,
+        _total: usize,
+    ) {
+        if step == openfare_rs_lib::progress::Step::ReadLock {
+            self.read_locks.lock().unwrap().push(package.name.clone());
+        }
+    }
+}
+
+#[test]
+fn test_shared_dependency_read_once() -> anyhow::Result<()> {
+    common::setup();
+    let progress = std::sync::Arc::new(RecordingProgress::default());
+    let extension = openfare_rs_lib::RsExtension::new().with_progress(progress.clone());
+    let workspace_path = common::fixtures_directory().join("shared_dependency_workspace");
+    let vendor_path = workspace_path.join("vendor");
+    let args = vec![
+        "--config".to_string(),
+        "source.crates-io.replace-with=\"vendored-sources\"".to_string(),
+        "--config".to_string(),
+        format!(
+            "source.vendored-sources.directory={}",
+            toml::Value::from(vendor_path.display().to_string())
+        ),
+    ];
+    let result = extension.resolve_workspace_members(&workspace_path, &args)?;
+
+    let members: Vec<(&str, Vec<(&str, bool)>)> = result
+        .locks
+        .iter()
+        .map(|(member, package_locks)| {
+            let locks = package_locks
+                .dependencies_locks
+                .iter()
+                .map(|(package, lock)| (package.name.as_str(), lock.is_some()))
+                .collect();
+            (member.name.as_str(), locks)
+        })
+        .collect();
+    assert_eq!(
+        members,
+        vec![
+            ("first", vec![("alpha", true), ("beta", false)]),
+            ("second", vec![("alpha", true)]),
+        ]
+    );
+
+    // alpha is a dependency of both members but its lock is read once.
+    let mut read_locks = progress.read_locks.lock().unwrap().clone();
+    read_locks.sort();
+    assert_eq!(read_locks, vec!["alpha", "beta", "first", "second"]);
+    Ok(())
+}